@@ -15,6 +15,25 @@ mod m20260109_210008_add_password;
 mod m20260109_211546_add_defenses;
 mod m20260109_220528_add_debris;
 mod m20260109_222210_add_coordinates;
+mod m20260110_091502_add_hyperspace_tech;
+mod m20260110_143210_add_fleet_movements;
+mod m20260111_101245_add_astrophysics_tech;
+mod m20260111_183020_add_npc_bases;
+mod m20260112_094530_add_vacation_mode;
+mod m20260112_162045_create_ranking;
+mod m20260113_110215_create_alliances;
+mod m20260114_093015_create_messages;
+mod m20260114_161240_create_notifications;
+mod m20260115_100830_add_cargo_ships;
+mod m20260115_153020_add_acs_groups;
+mod m20260116_091245_create_resource_ledger;
+mod m20260116_142030_create_universes;
+mod m20260117_093000_unique_planet_coords;
+mod m20260117_141500_add_planet_traits;
+mod m20260118_101500_create_planet_unit;
+mod m20260118_150000_create_levels;
+mod m20260119_094500_laser_battery_to_defenses;
+mod m20260119_160000_vacation_per_owner;
 
 pub struct Migrator;
 
@@ -37,6 +56,25 @@ impl MigratorTrait for Migrator {
             Box::new(m20260109_211546_add_defenses::Migration),
             Box::new(m20260109_220528_add_debris::Migration),
             Box::new(m20260109_222210_add_coordinates::Migration),
+            Box::new(m20260110_091502_add_hyperspace_tech::Migration),
+            Box::new(m20260110_143210_add_fleet_movements::Migration),
+            Box::new(m20260111_101245_add_astrophysics_tech::Migration),
+            Box::new(m20260111_183020_add_npc_bases::Migration),
+            Box::new(m20260112_094530_add_vacation_mode::Migration),
+            Box::new(m20260112_162045_create_ranking::Migration),
+            Box::new(m20260113_110215_create_alliances::Migration),
+            Box::new(m20260114_093015_create_messages::Migration),
+            Box::new(m20260114_161240_create_notifications::Migration),
+            Box::new(m20260115_100830_add_cargo_ships::Migration),
+            Box::new(m20260115_153020_add_acs_groups::Migration),
+            Box::new(m20260116_091245_create_resource_ledger::Migration),
+            Box::new(m20260116_142030_create_universes::Migration),
+            Box::new(m20260117_093000_unique_planet_coords::Migration),
+            Box::new(m20260117_141500_add_planet_traits::Migration),
+            Box::new(m20260118_101500_create_planet_unit::Migration),
+            Box::new(m20260118_150000_create_levels::Migration),
+            Box::new(m20260119_094500_laser_battery_to_defenses::Migration),
+            Box::new(m20260119_160000_vacation_per_owner::Migration),
         ]
     }
 }
@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Flottes en vol (une ligne par mouvement, supprimée au retour)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("fleet"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("planet_id")).uuid().not_null()) // Planète d'origine
+                    .col(ColumnDef::new(Alias::new("mission_type")).string().not_null()) // "expedition", ...
+                    .col(ColumnDef::new(Alias::new("status")).string().not_null()) // "outbound", "returning"
+                    .col(ColumnDef::new(Alias::new("target_galaxy")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("target_system")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("target_position")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("ships")).text().not_null()) // JSON { "light_hunter": 10, ... }
+                    .col(ColumnDef::new(Alias::new("cargo_metal")).double().not_null().default(0.0))
+                    .col(ColumnDef::new(Alias::new("cargo_crystal")).double().not_null().default(0.0))
+                    .col(ColumnDef::new(Alias::new("cargo_deuterium")).double().not_null().default(0.0))
+                    .col(ColumnDef::new(Alias::new("cargo_dark_matter")).double().not_null().default(0.0))
+                    .col(ColumnDef::new(Alias::new("departure_time")).timestamp().not_null())
+                    .col(ColumnDef::new(Alias::new("arrival_time")).timestamp().not_null())
+                    .col(ColumnDef::new(Alias::new("hold_hours")).integer().not_null().default(0)) // Durée de stationnement choisie
+                    .col(ColumnDef::new(Alias::new("hold_end")).timestamp().not_null())
+                    .col(ColumnDef::new(Alias::new("return_time")).timestamp().not_null())
+                    .col(ColumnDef::new(Alias::new("report")).text().null()) // Rapport livré au retour
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Matière noire (trouvée en expédition)
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("planet"))
+                .add_column(ColumnDef::new(Alias::new("dark_matter_amount")).double().not_null().default(0.0))
+                .to_owned(),
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("planet"))
+                .drop_column(Alias::new("dark_matter_amount"))
+                .to_owned(),
+        ).await?;
+
+        manager
+            .drop_table(Table::drop().table(Alias::new("fleet")).to_owned())
+            .await
+    }
+}
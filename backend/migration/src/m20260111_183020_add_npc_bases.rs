@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Repaires pirates contrôlés par le serveur
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("planet"))
+                .add_column(ColumnDef::new(Alias::new("is_npc")).boolean().not_null().default(false))
+                .to_owned(),
+        ).await?;
+
+        // 2. Cible exacte des flottes d'attaque
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("fleet"))
+                .add_column(ColumnDef::new(Alias::new("target_planet_id")).uuid().null())
+                .to_owned(),
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("fleet"))
+                .drop_column(Alias::new("target_planet_id"))
+                .to_owned(),
+        ).await?;
+
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("planet"))
+                .drop_column(Alias::new("is_npc"))
+                .to_owned(),
+        ).await
+    }
+}
@@ -0,0 +1,27 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("planet"))
+                .add_column(ColumnDef::new(Alias::new("vacation_start")).timestamp().null())
+                .add_column(ColumnDef::new(Alias::new("vacation_min_end")).timestamp().null())
+                .to_owned(),
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("planet"))
+                .drop_column(Alias::new("vacation_start"))
+                .drop_column(Alias::new("vacation_min_end"))
+                .to_owned(),
+        ).await
+    }
+}
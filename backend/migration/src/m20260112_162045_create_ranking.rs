@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Points par catégorie (1 point = 1000 ressources dépensées)
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("planet"))
+                .add_column(ColumnDef::new(Alias::new("points_economy")).double().not_null().default(0.0))
+                .add_column(ColumnDef::new(Alias::new("points_research")).double().not_null().default(0.0))
+                .add_column(ColumnDef::new(Alias::new("points_military_built")).double().not_null().default(0.0))
+                .add_column(ColumnDef::new(Alias::new("points_military_destroyed")).double().not_null().default(0.0))
+                .add_column(ColumnDef::new(Alias::new("points_military_lost")).double().not_null().default(0.0))
+                .to_owned(),
+        ).await?;
+
+        // 2. Estimation des points des planètes existantes (somme géométrique des coûts de chaque niveau)
+        manager.get_connection().execute_unprepared(
+            "UPDATE planet SET
+                points_economy = (
+                    75.0 * (power(1.5, metal_mine_level) - 1) / 0.5
+                  + 72.0 * (power(1.6, crystal_mine_level) - 1) / 0.6
+                  + 300.0 * (power(1.5, deuterium_mine_level) - 1) / 0.5
+                  + 800.0 * (power(2, research_lab_level) - 1)
+                ) / 1000.0,
+                points_research = (
+                    1200.0 * (power(2, energy_tech_level) - 1)
+                  + 1400.0 * (power(2, espionage_tech_level) - 1)
+                  + 6000.0 * (power(2, hyperspace_tech_level) - 1)
+                  + 16000.0 * (power(1.75, astrophysics_tech_level) - 1) / 0.75
+                ) / 1000.0,
+                points_military_built = (
+                    2100.0 * (power(2, laser_battery_level) - 1)
+                  + 4000.0 * light_hunter_count
+                  + 27000.0 * cruiser_count
+                  + 16000.0 * recycler_count
+                  + 1000.0 * spy_probe_count
+                  + 2000.0 * missile_launcher_count
+                  + 100000.0 * plasma_turret_count
+                ) / 1000.0
+            WHERE is_npc = false"
+        ).await?;
+
+        // 3. Instantanés du classement
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("ranking"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("planet_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("planet_name")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("category")).string().not_null()) // "total", "economy", ...
+                    .col(ColumnDef::new(Alias::new("rank")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("score")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("snapshot_at")).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ranking_category_snapshot")
+                    .table(Alias::new("ranking"))
+                    .col(Alias::new("category"))
+                    .col(Alias::new("snapshot_at"))
+                    .col(Alias::new("rank"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("ranking")).to_owned())
+            .await?;
+
+        manager.alter_table(
+            Table::alter()
+                .table(Alias::new("planet"))
+                .drop_column(Alias::new("points_economy"))
+                .drop_column(Alias::new("points_research"))
+                .drop_column(Alias::new("points_military_built"))
+                .drop_column(Alias::new("points_military_destroyed"))
+                .drop_column(Alias::new("points_military_lost"))
+                .to_owned(),
+        ).await
+    }
+}
@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Alliances
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("alliance"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("tag")).string().not_null().unique_key())
+                    .col(ColumnDef::new(Alias::new("name")).string().not_null().unique_key())
+                    .col(ColumnDef::new(Alias::new("description")).text().null())
+                    .col(ColumnDef::new(Alias::new("founder_id")).uuid().not_null()) // Planète du fondateur
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Rangs et permissions
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("alliance_rank"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("alliance_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("name")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("can_accept")).boolean().not_null().default(false))
+                    .col(ColumnDef::new(Alias::new("can_kick")).boolean().not_null().default(false))
+                    .col(ColumnDef::new(Alias::new("can_manage_ranks")).boolean().not_null().default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. Membres (une seule alliance par planète)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("alliance_member"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("planet_id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("alliance_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("rank_id")).uuid().null()) // NULL = simple membre
+                    .col(ColumnDef::new(Alias::new("joined_at")).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 4. Candidatures
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("alliance_application"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("alliance_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("planet_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("message")).text().null())
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in ["alliance_application", "alliance_member", "alliance_rank", "alliance"] {
+            manager
+                .drop_table(Table::drop().table(Alias::new(table)).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Messagerie
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("message"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("recipient_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("sender_id")).uuid().null()) // NULL = message système
+                    .col(ColumnDef::new(Alias::new("sender_name")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("folder")).string().not_null()) // "personal", "combat", ...
+                    .col(ColumnDef::new(Alias::new("subject")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("body")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("report")).text().null()) // Rapport JSON joint
+                    .col(ColumnDef::new(Alias::new("is_read")).boolean().not_null().default(false))
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_message_recipient_folder")
+                    .table(Alias::new("message"))
+                    .col(Alias::new("recipient_id"))
+                    .col(Alias::new("folder"))
+                    .col(Alias::new("created_at"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Droit d'envoyer des circulaires d'alliance
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("alliance_rank"))
+                    .add_column(ColumnDef::new(Alias::new("can_circular")).boolean().not_null().default(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("alliance_rank"))
+                    .drop_column(Alias::new("can_circular"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Alias::new("message")).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Boîte de notifications (plusieurs alertes non lues par joueur)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("notification"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("planet_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("kind")).string().not_null()) // "defense_report", "attack_report", ...
+                    .col(ColumnDef::new(Alias::new("payload")).text().not_null()) // JSON
+                    .col(ColumnDef::new(Alias::new("is_read")).boolean().not_null().default(false))
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notification_planet_unread")
+                    .table(Alias::new("notification"))
+                    .col(Alias::new("planet_id"))
+                    .col(Alias::new("is_read"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Reprise des rapports encore non lus
+        manager.get_connection().execute_unprepared(
+            "INSERT INTO notification (id, planet_id, kind, payload, is_read, created_at)
+             SELECT gen_random_uuid(), id,
+                    CASE WHEN unread_report LIKE '%\"is_defense\":true%' THEN 'defense_report'
+                         WHEN unread_report LIKE '%\"is_expedition\":true%' THEN 'expedition_report'
+                         ELSE 'attack_report' END,
+                    unread_report, false, now()
+             FROM planet WHERE unread_report IS NOT NULL"
+        ).await?;
+
+        // 3. L'ancienne colonne n'a plus lieu d'être
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("planet"))
+                    .drop_column(Alias::new("unread_report"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("planet"))
+                    .add_column(ColumnDef::new(Alias::new("unread_report")).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Alias::new("notification")).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Journal des mouvements de ressources (ajout seul, jamais modifié)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("resource_ledger"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("planet_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("metal")).double().not_null().default(0.0))
+                    .col(ColumnDef::new(Alias::new("crystal")).double().not_null().default(0.0))
+                    .col(ColumnDef::new(Alias::new("deuterium")).double().not_null().default(0.0))
+                    .col(ColumnDef::new(Alias::new("dark_matter")).double().not_null().default(0.0))
+                    .col(ColumnDef::new(Alias::new("reason")).string().not_null()) // "production", "construction", "plunder", ...
+                    .col(ColumnDef::new(Alias::new("related_id")).uuid().null()) // Flotte, planète adverse...
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_resource_ledger_planet_date")
+                    .table(Alias::new("resource_ledger"))
+                    .col(Alias::new("planet_id"))
+                    .col(Alias::new("created_at"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("resource_ledger")).to_owned())
+            .await
+    }
+}
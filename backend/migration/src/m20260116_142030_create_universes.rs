@@ -0,0 +1,97 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Tables rattachées à un univers
+const SCOPED_TABLES: [&str; 3] = ["planet", "alliance", "ranking"];
+
+// Réglages de l'univers historique : variables UNIVERSE_* si définies, sinon valeurs d'origine
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Univers et leurs réglages
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("universe"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alias::new("name")).string().not_null().unique_key())
+                    .col(ColumnDef::new(Alias::new("economy_speed")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("research_speed")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("fleet_speed")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("expedition_speed")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("galaxies")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("systems_per_galaxy")).integer().not_null())
+                    .col(ColumnDef::new(Alias::new("debris_ratio")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("defense_debris_ratio")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("is_open")).boolean().not_null().default(true)) // Inscriptions ouvertes
+                    .col(ColumnDef::new(Alias::new("created_at")).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Univers historique, avec les réglages de l'environnement
+        let db = manager.get_connection();
+        db.execute_unprepared(&format!(
+            "INSERT INTO universe (id, name, economy_speed, research_speed, fleet_speed, expedition_speed,
+                                   galaxies, systems_per_galaxy, debris_ratio, defense_debris_ratio, is_open, created_at)
+             VALUES (gen_random_uuid(), 'Univers 1', {}, {}, {}, {}, {}, {}, {}, {}, true, now())",
+            env_or("UNIVERSE_ECONOMY_SPEED", 500000.0),
+            env_or("UNIVERSE_RESEARCH_SPEED", 500000.0),
+            env_or("UNIVERSE_FLEET_SPEED", 500000.0),
+            env_or("UNIVERSE_EXPEDITION_SPEED", 500000.0),
+            env_or("UNIVERSE_GALAXIES", 9),
+            env_or("UNIVERSE_SYSTEMS", 499),
+            env_or("UNIVERSE_DEBRIS_RATIO", 0.3),
+            env_or("UNIVERSE_DEFENSE_DEBRIS_RATIO", 0.0),
+        )).await?;
+
+        // 3. Les données existantes rejoignent l'univers historique
+        for table in SCOPED_TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(ColumnDef::new(Alias::new("universe_id")).uuid().null())
+                        .to_owned(),
+                )
+                .await?;
+            db.execute_unprepared(&format!("UPDATE {table} SET universe_id = (SELECT id FROM universe LIMIT 1)")).await?;
+            db.execute_unprepared(&format!("ALTER TABLE {table} ALTER COLUMN universe_id SET NOT NULL")).await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_planet_universe_coords")
+                    .table(Alias::new("planet"))
+                    .col(Alias::new("universe_id"))
+                    .col(Alias::new("galaxy"))
+                    .col(Alias::new("system"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in SCOPED_TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_column(Alias::new("universe_id"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(Alias::new("universe")).to_owned())
+            .await
+    }
+}
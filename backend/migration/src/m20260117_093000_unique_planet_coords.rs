@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement};
+use std::collections::HashSet;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const POSITIONS_PER_SYSTEM: i32 = 15;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // 1. L'ancien placement a pu créer des doublons : on déplace les planètes en trop
+        //    vers l'emplacement libre suivant de la même galaxie (la plus ancienne reste en place)
+        let rows = db
+            .query_all(Statement::from_string(
+                backend,
+                "SELECT p.id::text AS id, p.universe_id::text AS universe_id, p.galaxy, p.system, p.position,
+                        u.systems_per_galaxy
+                 FROM planet p JOIN universe u ON u.id = p.universe_id
+                 ORDER BY p.universe_id, p.galaxy, p.system, p.position, p.last_update",
+            ))
+            .await?;
+
+        let mut occupied: HashSet<(String, i32, i32, i32)> = HashSet::new();
+        let mut duplicates = Vec::new();
+        for row in &rows {
+            let universe: String = row.try_get("", "universe_id")?;
+            let slot = (universe, row.try_get("", "galaxy")?, row.try_get("", "system")?, row.try_get("", "position")?);
+            if !occupied.insert(slot.clone()) {
+                let id: String = row.try_get("", "id")?;
+                let systems: i32 = row.try_get("", "systems_per_galaxy")?;
+                duplicates.push((id, slot, systems));
+            }
+        }
+
+        for (id, (universe, galaxy, system, _), systems) in duplicates {
+            let free = (0..systems)
+                .map(|offset| (system - 1 + offset) % systems + 1)
+                .flat_map(|s| (1..=POSITIONS_PER_SYSTEM).map(move |p| (s, p)))
+                .find(|&(s, p)| !occupied.contains(&(universe.clone(), galaxy, s, p)));
+            let Some((s, p)) = free else {
+                return Err(DbErr::Migration(format!("Galaxie {} pleine : impossible de déplacer la planète {}", galaxy, id)));
+            };
+            occupied.insert((universe.clone(), galaxy, s, p));
+            db.execute_unprepared(&format!("UPDATE planet SET system = {s}, position = {p} WHERE id = '{id}'")).await?;
+        }
+
+        // 2. Un emplacement = une planète
+        manager
+            .drop_index(Index::drop().name("idx_planet_universe_coords").table(Alias::new("planet")).to_owned())
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_planet_coords")
+                    .table(Alias::new("planet"))
+                    .col(Alias::new("universe_id"))
+                    .col(Alias::new("galaxy"))
+                    .col(Alias::new("system"))
+                    .col(Alias::new("position"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_planet_coords").table(Alias::new("planet")).to_owned())
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_planet_universe_coords")
+                    .table(Alias::new("planet"))
+                    .col(Alias::new("universe_id"))
+                    .col(Alias::new("galaxy"))
+                    .col(Alias::new("system"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
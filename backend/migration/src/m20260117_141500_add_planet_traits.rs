@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("planet"))
+                    .add_column(ColumnDef::new(Alias::new("diameter")).integer().not_null().default(0)) // km
+                    .add_column(ColumnDef::new(Alias::new("fields_max")).integer().not_null().default(0))
+                    .add_column(ColumnDef::new(Alias::new("temperature_min")).integer().not_null().default(0)) // °C
+                    .add_column(ColumnDef::new(Alias::new("temperature_max")).integer().not_null().default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Planètes existantes : valeurs moyennes de leur position, sans jamais passer
+        // sous le nombre de cases déjà construites
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "UPDATE planet SET
+                fields_max = GREATEST(
+                    CASE WHEN position <= 3 THEN 60
+                         WHEN position <= 6 THEN 130
+                         WHEN position <= 9 THEN 170
+                         WHEN position <= 12 THEN 145
+                         ELSE 105 END,
+                    metal_mine_level + crystal_mine_level + deuterium_mine_level + research_lab_level + laser_battery_level),
+                temperature_max = 240 - (position - 1) * 27,
+                temperature_min = 200 - (position - 1) * 27"
+        ).await?;
+        db.execute_unprepared("UPDATE planet SET diameter = ROUND(SQRT(fields_max) * 1000)").await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("planet"))
+                    .drop_column(Alias::new("diameter"))
+                    .drop_column(Alias::new("fields_max"))
+                    .drop_column(Alias::new("temperature_min"))
+                    .drop_column(Alias::new("temperature_max"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
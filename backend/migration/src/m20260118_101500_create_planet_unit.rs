@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Anciennes colonnes de planet -> type d'unité
+const UNIT_COLUMNS: [(&str, &str); 8] = [
+    ("light_hunter_count", "light_hunter"),
+    ("cruiser_count", "cruiser"),
+    ("recycler_count", "recycler"),
+    ("spy_probe_count", "spy_probe"),
+    ("small_cargo_count", "small_cargo"),
+    ("large_cargo_count", "large_cargo"),
+    ("missile_launcher_count", "missile_launcher"),
+    ("plasma_turret_count", "plasma_turret"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Inventaire des vaisseaux et défenses : une ligne par planète et type d'unité
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("planet_unit"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("planet_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("unit_type")).string().not_null()) // Voir le catalogue (game_logic)
+                    .col(ColumnDef::new(Alias::new("count")).integer().not_null().default(0))
+                    .primary_key(Index::create().col(Alias::new("planet_id")).col(Alias::new("unit_type")))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Reprise des données puis suppression des colonnes
+        let db = manager.get_connection();
+        for (column, unit_type) in UNIT_COLUMNS {
+            db.execute_unprepared(&format!(
+                "INSERT INTO planet_unit (planet_id, unit_type, count)
+                 SELECT id, '{unit_type}', {column} FROM planet WHERE {column} > 0"
+            )).await?;
+            manager
+                .alter_table(Table::alter().table(Alias::new("planet")).drop_column(Alias::new(column)).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for (column, unit_type) in UNIT_COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("planet"))
+                        .add_column(ColumnDef::new(Alias::new(column)).integer().not_null().default(0))
+                        .to_owned(),
+                )
+                .await?;
+            db.execute_unprepared(&format!(
+                "UPDATE planet SET {column} = u.count FROM planet_unit u
+                 WHERE u.planet_id = planet.id AND u.unit_type = '{unit_type}'"
+            )).await?;
+        }
+        manager
+            .drop_table(Table::drop().table(Alias::new("planet_unit")).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,117 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Anciennes colonnes de planet -> type de bâtiment (propre à chaque planète)
+const BUILDING_COLUMNS: [(&str, &str); 5] = [
+    ("metal_mine_level", "metal"),
+    ("crystal_mine_level", "crystal"),
+    ("deuterium_mine_level", "deuterium"),
+    ("research_lab_level", "research"),
+    ("laser_battery_level", "laser"),
+];
+
+// Anciennes colonnes de planet -> type de recherche (commune à tout le compte)
+const RESEARCH_COLUMNS: [(&str, &str); 4] = [
+    ("energy_tech_level", "energy_tech"),
+    ("espionage_tech_level", "espionage"),
+    ("hyperspace_tech_level", "hyperspace"),
+    ("astrophysics_tech_level", "astrophysics"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Niveaux des bâtiments : une ligne par planète et bâtiment
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("planet_building"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("planet_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("building_type")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("level")).integer().not_null().default(0))
+                    .primary_key(Index::create().col(Alias::new("planet_id")).col(Alias::new("building_type")))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Niveaux des recherches : une ligne par joueur (owner_id) et technologie
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("player_research"))
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("owner_id")).uuid().not_null())
+                    .col(ColumnDef::new(Alias::new("research_type")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("level")).integer().not_null().default(0))
+                    .primary_key(Index::create().col(Alias::new("owner_id")).col(Alias::new("research_type")))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Reprise des données puis suppression des colonnes
+        let db = manager.get_connection();
+        for (column, building_type) in BUILDING_COLUMNS {
+            db.execute_unprepared(&format!(
+                "INSERT INTO planet_building (planet_id, building_type, level)
+                 SELECT id, '{building_type}', {column} FROM planet WHERE {column} > 0"
+            )).await?;
+            manager
+                .alter_table(Table::alter().table(Alias::new("planet")).drop_column(Alias::new(column)).to_owned())
+                .await?;
+        }
+        // Un joueur possédant plusieurs planètes garde son meilleur niveau
+        for (column, research_type) in RESEARCH_COLUMNS {
+            db.execute_unprepared(&format!(
+                "INSERT INTO player_research (owner_id, research_type, level)
+                 SELECT owner_id, '{research_type}', MAX({column}) FROM planet
+                 GROUP BY owner_id HAVING MAX({column}) > 0"
+            )).await?;
+            manager
+                .alter_table(Table::alter().table(Alias::new("planet")).drop_column(Alias::new(column)).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for (column, building_type) in BUILDING_COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("planet"))
+                        .add_column(ColumnDef::new(Alias::new(column)).integer().not_null().default(0))
+                        .to_owned(),
+                )
+                .await?;
+            db.execute_unprepared(&format!(
+                "UPDATE planet SET {column} = b.level FROM planet_building b
+                 WHERE b.planet_id = planet.id AND b.building_type = '{building_type}'"
+            )).await?;
+        }
+        for (column, research_type) in RESEARCH_COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("planet"))
+                        .add_column(ColumnDef::new(Alias::new(column)).integer().not_null().default(0))
+                        .to_owned(),
+                )
+                .await?;
+            db.execute_unprepared(&format!(
+                "UPDATE planet SET {column} = r.level FROM player_research r
+                 WHERE r.owner_id = planet.owner_id AND r.research_type = '{research_type}'"
+            )).await?;
+        }
+        manager
+            .drop_table(Table::drop().table(Alias::new("player_research")).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("planet_building")).to_owned())
+            .await
+    }
+}
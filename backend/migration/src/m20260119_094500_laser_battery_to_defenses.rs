@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // La batterie laser devient une défense dénombrable (light_laser).
+        // Un niveau L a coûté autant que 2^L - 1 lasers légers : on convertit à valeur égale,
+        // en terminant au passage les améliorations en cours (niveau plafonné à 20).
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "INSERT INTO planet_unit (planet_id, unit_type, count)
+             SELECT p.id, 'light_laser',
+                    POWER(2, LEAST(COALESCE(b.level, 0) + CASE WHEN p.construction_type = 'laser' THEN 1 ELSE 0 END, 20))::int - 1
+             FROM planet p
+             LEFT JOIN planet_building b ON b.planet_id = p.id AND b.building_type = 'laser'
+             WHERE COALESCE(b.level, 0) > 0 OR p.construction_type = 'laser'
+             ON CONFLICT (planet_id, unit_type) DO UPDATE SET count = planet_unit.count + EXCLUDED.count"
+        ).await?;
+        db.execute_unprepared("DELETE FROM planet_building WHERE building_type = 'laser'").await?;
+        db.execute_unprepared(
+            "UPDATE planet SET construction_type = NULL, construction_end = NULL WHERE construction_type = 'laser'"
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Conversion inverse approchée : niveau = log2(lasers + 1), arrondi à l'inférieur
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "INSERT INTO planet_building (planet_id, building_type, level)
+             SELECT planet_id, 'laser', FLOOR(LOG(2, count + 1))::int FROM planet_unit
+             WHERE unit_type = 'light_laser'"
+        ).await?;
+        db.execute_unprepared("DELETE FROM planet_unit WHERE unit_type = 'light_laser'").await?;
+
+        Ok(())
+    }
+}
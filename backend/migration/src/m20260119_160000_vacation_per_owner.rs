@@ -0,0 +1,25 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Le mode vacances vaut pour tout le compte : les autres planètes d'un joueur
+        // en vacances passent elles aussi en vacances, avec la même durée minimale
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "UPDATE planet SET vacation_start = v.vacation_start, vacation_min_end = v.vacation_min_end
+             FROM (SELECT owner_id, MIN(vacation_start) AS vacation_start, MAX(vacation_min_end) AS vacation_min_end
+                   FROM planet WHERE vacation_start IS NOT NULL GROUP BY owner_id) v
+             WHERE planet.owner_id = v.owner_id AND planet.vacation_start IS NULL"
+        ).await?;
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Rien à défaire : l'état par planète d'origine n'est pas conservé
+        Ok(())
+    }
+}
@@ -0,0 +1,652 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{alliance, alliance_application, alliance_member, alliance_rank, planet, ranking};
+use crate::{messaging, AppState};
+
+type ApiResult = Result<Json<Value>, (StatusCode, Json<Value>)>;
+
+fn error(code: StatusCode, message: &str) -> (StatusCode, Json<Value>) {
+    (code, Json(json!({ "error": message })))
+}
+
+fn db_error(_: DbErr) -> (StatusCode, Json<Value>) {
+    error(StatusCode::INTERNAL_SERVER_ERROR, "Erreur DB")
+}
+
+fn current_planet_id(params: &HashMap<String, String>) -> Result<Uuid, (StatusCode, Json<Value>)> {
+    params.get("current_planet_id")
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| error(StatusCode::UNAUTHORIZED, "ID Planète invalide"))
+}
+
+// --- PERMISSIONS ---
+
+#[derive(Default)]
+struct Permissions {
+    is_founder: bool,
+    can_accept: bool,
+    can_kick: bool,
+    can_manage_ranks: bool,
+    can_circular: bool,
+}
+
+// Adhésion de la planète à cette alliance et ses droits, ou 403
+async fn require_member(db: &impl ConnectionTrait, alliance: &alliance::Model, planet_id: Uuid) -> Result<(alliance_member::Model, Permissions), (StatusCode, Json<Value>)> {
+    let member = alliance_member::Entity::find_by_id(planet_id)
+        .one(db)
+        .await
+        .map_err(db_error)?
+        .filter(|m| m.alliance_id == alliance.id)
+        .ok_or_else(|| error(StatusCode::FORBIDDEN, "Vous n'êtes pas membre de cette alliance"))?;
+
+    let perms = if alliance.founder_id == planet_id {
+        Permissions { is_founder: true, can_accept: true, can_kick: true, can_manage_ranks: true, can_circular: true }
+    } else if let Some(rank_id) = member.rank_id {
+        match alliance_rank::Entity::find_by_id(rank_id).one(db).await.map_err(db_error)? {
+            Some(rank) => Permissions {
+                is_founder: false,
+                can_accept: rank.can_accept,
+                can_kick: rank.can_kick,
+                can_manage_ranks: rank.can_manage_ranks,
+                can_circular: rank.can_circular,
+            },
+            None => Permissions::default(),
+        }
+    } else {
+        Permissions::default()
+    };
+
+    Ok((member, perms))
+}
+
+async fn find_alliance(db: &impl ConnectionTrait, id: Uuid) -> Result<alliance::Model, (StatusCode, Json<Value>)> {
+    alliance::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "Alliance introuvable"))
+}
+
+// --- UTILITAIRES PARTAGÉS ---
+
+// Tag d'alliance de chaque planète (absente si sans alliance)
+pub async fn get_tags(db: &DatabaseConnection, planet_ids: Vec<Uuid>) -> HashMap<Uuid, String> {
+    let members = alliance_member::Entity::find()
+        .filter(alliance_member::Column::PlanetId.is_in(planet_ids))
+        .all(db)
+        .await
+        .unwrap_or_default();
+    let alliance_ids: Vec<Uuid> = members.iter().map(|m| m.alliance_id).collect();
+    let tags: HashMap<Uuid, String> = alliance::Entity::find()
+        .filter(alliance::Column::Id.is_in(alliance_ids))
+        .all(db)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|a| (a.id, a.tag))
+        .collect();
+
+    members
+        .into_iter()
+        .filter_map(|m| tags.get(&m.alliance_id).map(|tag| (m.planet_id, tag.clone())))
+        .collect()
+}
+
+pub async fn same_alliance(db: &impl ConnectionTrait, a: Uuid, b: Uuid) -> Result<bool, DbErr> {
+    let members = alliance_member::Entity::find()
+        .filter(alliance_member::Column::PlanetId.is_in([a, b]))
+        .all(db)
+        .await?;
+    Ok(members.len() == 2 && members[0].alliance_id == members[1].alliance_id)
+}
+
+// --- CRÉATION / DISSOLUTION ---
+
+#[derive(Deserialize)]
+pub struct CreateAlliancePayload {
+    pub tag: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+// POST /alliances?current_planet_id=...
+pub async fn create_alliance_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Json(payload): Json<CreateAlliancePayload>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let txn = state.db.begin().await.map_err(db_error)?;
+    let tag = payload.tag.trim().to_uppercase();
+    let name = payload.name.trim().to_string();
+
+    if !(3..=8).contains(&tag.chars().count()) || !(3..=30).contains(&name.chars().count()) {
+        return Err(error(StatusCode::BAD_REQUEST, "Tag (3-8) ou nom (3-30) invalide"));
+    }
+    if alliance_member::Entity::find_by_id(planet_id).one(&txn).await.map_err(db_error)?.is_some() {
+        return Err(error(StatusCode::CONFLICT, "Vous êtes déjà dans une alliance"));
+    }
+    let founder = planet::Entity::find_by_id(planet_id)
+        .one(&txn)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "Planète inconnue"))?;
+
+    let taken = alliance::Entity::find()
+        .filter(alliance::Column::Tag.eq(&tag).or(alliance::Column::Name.eq(&name)))
+        .one(&txn)
+        .await
+        .map_err(db_error)?;
+    if taken.is_some() {
+        return Err(error(StatusCode::CONFLICT, "Tag ou nom déjà utilisé"));
+    }
+
+    let now = Utc::now().naive_utc();
+    let created = alliance::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        tag: Set(tag),
+        name: Set(name),
+        description: Set(payload.description),
+        founder_id: Set(planet_id),
+        universe_id: Set(founder.universe_id), // L'alliance ne recrute que dans cet univers
+        created_at: Set(now),
+    }
+    .insert(&txn)
+    .await
+    .map_err(db_error)?;
+
+    alliance_member::ActiveModel {
+        planet_id: Set(planet_id),
+        alliance_id: Set(created.id),
+        rank_id: Set(None),
+        joined_at: Set(now),
+    }
+    .insert(&txn)
+    .await
+    .map_err(db_error)?;
+
+    txn.commit().await.map_err(db_error)?;
+    Ok(Json(json!({ "status": "success", "alliance": created })))
+}
+
+// POST /alliances/:id/disband?current_planet_id=...
+pub async fn disband_alliance_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let txn = state.db.begin().await.map_err(db_error)?;
+    let found = find_alliance(&txn, id).await?;
+    let (_, perms) = require_member(&txn, &found, planet_id).await?;
+    if !perms.is_founder {
+        return Err(error(StatusCode::FORBIDDEN, "Seul le fondateur peut dissoudre l'alliance"));
+    }
+
+    alliance_application::Entity::delete_many().filter(alliance_application::Column::AllianceId.eq(id)).exec(&txn).await.map_err(db_error)?;
+    alliance_member::Entity::delete_many().filter(alliance_member::Column::AllianceId.eq(id)).exec(&txn).await.map_err(db_error)?;
+    alliance_rank::Entity::delete_many().filter(alliance_rank::Column::AllianceId.eq(id)).exec(&txn).await.map_err(db_error)?;
+    found.delete(&txn).await.map_err(db_error)?;
+
+    txn.commit().await.map_err(db_error)?;
+    Ok(Json(json!({ "status": "success" })))
+}
+
+// --- CONSULTATION ---
+
+#[derive(Serialize)]
+struct MemberView {
+    planet_id: Uuid,
+    planet_name: String,
+    rank: String,
+    is_founder: bool,
+    joined_at: chrono::NaiveDateTime,
+}
+
+// GET /alliances/:id
+pub async fn get_alliance_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> ApiResult {
+    let found = find_alliance(&state.db, id).await?;
+
+    let members = alliance_member::Entity::find()
+        .filter(alliance_member::Column::AllianceId.eq(id))
+        .order_by_asc(alliance_member::Column::JoinedAt)
+        .all(&state.db)
+        .await
+        .map_err(db_error)?;
+    let ranks = alliance_rank::Entity::find()
+        .filter(alliance_rank::Column::AllianceId.eq(id))
+        .all(&state.db)
+        .await
+        .map_err(db_error)?;
+    let names: HashMap<Uuid, String> = planet::Entity::find()
+        .filter(planet::Column::Id.is_in(members.iter().map(|m| m.planet_id).collect::<Vec<_>>()))
+        .all(&state.db)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|p| (p.id, p.name))
+        .collect();
+
+    let member_views: Vec<MemberView> = members.iter().map(|m| {
+        let is_founder = m.planet_id == found.founder_id;
+        let rank = if is_founder {
+            "Fondateur".to_string()
+        } else {
+            m.rank_id
+                .and_then(|rank_id| ranks.iter().find(|r| r.id == rank_id))
+                .map(|r| r.name.clone())
+                .unwrap_or_else(|| "Membre".to_string())
+        };
+        MemberView {
+            planet_id: m.planet_id,
+            planet_name: names.get(&m.planet_id).cloned().unwrap_or_default(),
+            rank,
+            is_founder,
+            joined_at: m.joined_at,
+        }
+    }).collect();
+
+    Ok(Json(json!({ "alliance": found, "members": member_views, "ranks": ranks })))
+}
+
+// --- CANDIDATURES ---
+
+#[derive(Deserialize)]
+pub struct ApplyPayload {
+    pub message: Option<String>,
+}
+
+// POST /alliances/:id/apply?current_planet_id=...
+pub async fn apply_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Json(payload): Json<ApplyPayload>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let found = find_alliance(&state.db, id).await?;
+    let applicant = planet::Entity::find_by_id(planet_id)
+        .one(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "Planète inconnue"))?;
+    if applicant.universe_id != found.universe_id {
+        return Err(error(StatusCode::FORBIDDEN, "Alliance d'un autre univers"));
+    }
+
+    if alliance_member::Entity::find_by_id(planet_id).one(&state.db).await.map_err(db_error)?.is_some() {
+        return Err(error(StatusCode::CONFLICT, "Vous êtes déjà dans une alliance"));
+    }
+    let pending = alliance_application::Entity::find()
+        .filter(alliance_application::Column::AllianceId.eq(id))
+        .filter(alliance_application::Column::PlanetId.eq(planet_id))
+        .one(&state.db)
+        .await
+        .map_err(db_error)?;
+    if pending.is_some() {
+        return Err(error(StatusCode::CONFLICT, "Candidature déjà envoyée"));
+    }
+
+    let application = alliance_application::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        alliance_id: Set(id),
+        planet_id: Set(planet_id),
+        message: Set(payload.message),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(json!({ "status": "success", "application": application })))
+}
+
+// GET /alliances/:id/applications?current_planet_id=...
+pub async fn list_applications_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let found = find_alliance(&state.db, id).await?;
+    let (_, perms) = require_member(&state.db, &found, planet_id).await?;
+    if !perms.can_accept {
+        return Err(error(StatusCode::FORBIDDEN, "Permission insuffisante"));
+    }
+
+    let applications = alliance_application::Entity::find()
+        .filter(alliance_application::Column::AllianceId.eq(id))
+        .order_by_asc(alliance_application::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!(applications)))
+}
+
+// POST /alliances/:id/applications/:application_id/:decision?current_planet_id=...
+pub async fn decide_application_handler(
+    Path((id, application_id, decision)): Path<(Uuid, Uuid, String)>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let txn = state.db.begin().await.map_err(db_error)?;
+    let found = find_alliance(&txn, id).await?;
+    let (_, perms) = require_member(&txn, &found, planet_id).await?;
+    if !perms.can_accept {
+        return Err(error(StatusCode::FORBIDDEN, "Permission insuffisante"));
+    }
+
+    let application = alliance_application::Entity::find_by_id(application_id)
+        .one(&txn)
+        .await
+        .map_err(db_error)?
+        .filter(|a| a.alliance_id == id)
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "Candidature introuvable"))?;
+
+    match decision.as_str() {
+        "accept" => {
+            // Le candidat a pu rejoindre une autre alliance entre-temps
+            if alliance_member::Entity::find_by_id(application.planet_id).one(&txn).await.map_err(db_error)?.is_some() {
+                application.delete(&txn).await.map_err(db_error)?;
+                txn.commit().await.map_err(db_error)?;
+                return Err(error(StatusCode::CONFLICT, "Le candidat est déjà dans une alliance"));
+            }
+            alliance_member::ActiveModel {
+                planet_id: Set(application.planet_id),
+                alliance_id: Set(id),
+                rank_id: Set(None),
+                joined_at: Set(Utc::now().naive_utc()),
+            }
+            .insert(&txn)
+            .await
+            .map_err(db_error)?;
+
+            // Ses autres candidatures deviennent caduques
+            alliance_application::Entity::delete_many()
+                .filter(alliance_application::Column::PlanetId.eq(application.planet_id))
+                .exec(&txn)
+                .await
+                .map_err(db_error)?;
+        }
+        "reject" => {
+            application.delete(&txn).await.map_err(db_error)?;
+        }
+        _ => return Err(error(StatusCode::BAD_REQUEST, "Décision inconnue")),
+    }
+
+    txn.commit().await.map_err(db_error)?;
+    Ok(Json(json!({ "status": "success" })))
+}
+
+// --- MEMBRES ---
+
+#[derive(Deserialize)]
+pub struct CircularPayload {
+    pub subject: String,
+    pub body: String,
+}
+
+// POST /alliances/:id/leave?current_planet_id=...
+pub async fn leave_alliance_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let found = find_alliance(&state.db, id).await?;
+    let (member, perms) = require_member(&state.db, &found, planet_id).await?;
+    if perms.is_founder {
+        return Err(error(StatusCode::CONFLICT, "Le fondateur doit dissoudre l'alliance"));
+    }
+
+    member.delete(&state.db).await.map_err(db_error)?;
+    Ok(Json(json!({ "status": "success" })))
+}
+
+// POST /alliances/:id/kick/:planet_id?current_planet_id=...
+pub async fn kick_member_handler(
+    Path((id, target_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let found = find_alliance(&state.db, id).await?;
+    let (_, perms) = require_member(&state.db, &found, planet_id).await?;
+    if !perms.can_kick {
+        return Err(error(StatusCode::FORBIDDEN, "Permission insuffisante"));
+    }
+    if target_id == found.founder_id || target_id == planet_id {
+        return Err(error(StatusCode::BAD_REQUEST, "Ce membre ne peut pas être exclu"));
+    }
+
+    let (target, _) = require_member(&state.db, &found, target_id).await
+        .map_err(|_| error(StatusCode::NOT_FOUND, "Membre introuvable"))?;
+    target.delete(&state.db).await.map_err(db_error)?;
+
+    Ok(Json(json!({ "status": "success" })))
+}
+
+// POST /alliances/:id/circular?current_planet_id=... (message à tous les membres)
+pub async fn circular_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Json(payload): Json<CircularPayload>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let txn = state.db.begin().await.map_err(db_error)?;
+    let found = find_alliance(&txn, id).await?;
+    let (_, perms) = require_member(&txn, &found, planet_id).await?;
+    if !perms.can_circular {
+        return Err(error(StatusCode::FORBIDDEN, "Permission insuffisante"));
+    }
+    messaging::validate(&payload.subject, &payload.body)?;
+
+    let sender_name = planet::Entity::find_by_id(planet_id)
+        .one(&txn)
+        .await
+        .map_err(db_error)?
+        .map(|p| p.name)
+        .unwrap_or_default();
+    let members = alliance_member::Entity::find()
+        .filter(alliance_member::Column::AllianceId.eq(id))
+        .all(&txn)
+        .await
+        .map_err(db_error)?;
+
+    let subject = format!("[{}] {}", found.tag, payload.subject.trim());
+    let bus = state.events.deferred();
+    for m in &members {
+        messaging::deliver(&txn, &bus, m.planet_id, Some((planet_id, &sender_name)), "alliance", &subject, payload.body.trim().to_string(), None)
+            .await
+            .map_err(db_error)?;
+    }
+
+    txn.commit().await.map_err(db_error)?;
+    bus.flush();
+    Ok(Json(json!({ "status": "success", "recipients": members.len() })))
+}
+
+// --- RANGS ---
+
+#[derive(Deserialize)]
+pub struct RankPayload {
+    pub name: String,
+    #[serde(default)]
+    pub can_accept: bool,
+    #[serde(default)]
+    pub can_kick: bool,
+    #[serde(default)]
+    pub can_manage_ranks: bool,
+    #[serde(default)]
+    pub can_circular: bool,
+}
+
+// POST /alliances/:id/ranks?current_planet_id=...
+pub async fn create_rank_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Json(payload): Json<RankPayload>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let found = find_alliance(&state.db, id).await?;
+    let (_, perms) = require_member(&state.db, &found, planet_id).await?;
+    if !perms.can_manage_ranks {
+        return Err(error(StatusCode::FORBIDDEN, "Permission insuffisante"));
+    }
+    if payload.name.trim().is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "Nom de rang invalide"));
+    }
+
+    let rank = alliance_rank::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        alliance_id: Set(id),
+        name: Set(payload.name.trim().to_string()),
+        can_accept: Set(payload.can_accept),
+        can_kick: Set(payload.can_kick),
+        can_manage_ranks: Set(payload.can_manage_ranks),
+        can_circular: Set(payload.can_circular),
+    }
+    .insert(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(json!({ "status": "success", "rank": rank })))
+}
+
+#[derive(Deserialize)]
+pub struct AssignRankPayload {
+    pub rank_id: Option<Uuid>, // None = simple membre
+}
+
+// POST /alliances/:id/members/:planet_id/rank?current_planet_id=...
+pub async fn assign_rank_handler(
+    Path((id, target_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Json(payload): Json<AssignRankPayload>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let found = find_alliance(&state.db, id).await?;
+    let (_, perms) = require_member(&state.db, &found, planet_id).await?;
+    if !perms.can_manage_ranks {
+        return Err(error(StatusCode::FORBIDDEN, "Permission insuffisante"));
+    }
+    if target_id == found.founder_id {
+        return Err(error(StatusCode::BAD_REQUEST, "Le fondateur n'a pas de rang"));
+    }
+
+    if let Some(rank_id) = payload.rank_id {
+        let rank = alliance_rank::Entity::find_by_id(rank_id).one(&state.db).await.map_err(db_error)?;
+        if rank.filter(|r| r.alliance_id == id).is_none() {
+            return Err(error(StatusCode::NOT_FOUND, "Rang introuvable"));
+        }
+    }
+
+    let (target, _) = require_member(&state.db, &found, target_id).await
+        .map_err(|_| error(StatusCode::NOT_FOUND, "Membre introuvable"))?;
+    let mut active: alliance_member::ActiveModel = target.into();
+    active.rank_id = Set(payload.rank_id);
+    active.update(&state.db).await.map_err(db_error)?;
+
+    Ok(Json(json!({ "status": "success" })))
+}
+
+// --- CLASSEMENT DES ALLIANCES ---
+
+#[derive(Serialize)]
+struct AllianceRankItem {
+    rank: usize,
+    id: Uuid,
+    tag: String,
+    name: String,
+    members: usize,
+    score: i32,
+    is_mine: bool,
+}
+
+// GET /ranking/alliances?current_planet_id=... (somme des scores du dernier instantané de l'univers)
+pub async fn get_alliance_ranking_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = params.get("current_planet_id").and_then(|s| Uuid::parse_str(s).ok()).unwrap_or_default();
+
+    let universe_id = state.universes
+        .resolve(&state.db, &params)
+        .await
+        .map_err(|code| error(code, "Univers introuvable"))?
+        .id;
+
+    let alliances = alliance::Entity::find()
+        .filter(alliance::Column::UniverseId.eq(universe_id))
+        .all(&state.db)
+        .await
+        .map_err(db_error)?;
+    let members = alliance_member::Entity::find()
+        .filter(alliance_member::Column::AllianceId.is_in(alliances.iter().map(|a| a.id).collect::<Vec<_>>()))
+        .all(&state.db)
+        .await
+        .map_err(db_error)?;
+
+    let latest = ranking::Entity::find()
+        .filter(ranking::Column::UniverseId.eq(universe_id))
+        .filter(ranking::Column::Category.eq("total"))
+        .order_by_desc(ranking::Column::SnapshotAt)
+        .one(&state.db)
+        .await
+        .map_err(db_error)?;
+    let scores: HashMap<Uuid, f64> = match latest {
+        Some(latest) => ranking::Entity::find()
+            .filter(ranking::Column::UniverseId.eq(universe_id))
+            .filter(ranking::Column::Category.eq("total"))
+            .filter(ranking::Column::SnapshotAt.eq(latest.snapshot_at))
+            .filter(ranking::Column::PlanetId.is_in(members.iter().map(|m| m.planet_id).collect::<Vec<_>>()))
+            .all(&state.db)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|r| (r.planet_id, r.score))
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    let mut items: Vec<AllianceRankItem> = alliances.into_iter().map(|a| {
+        let roster: Vec<&alliance_member::Model> = members.iter().filter(|m| m.alliance_id == a.id).collect();
+        AllianceRankItem {
+            rank: 0,
+            id: a.id,
+            tag: a.tag,
+            name: a.name,
+            members: roster.len(),
+            score: roster.iter().map(|m| scores.get(&m.planet_id).copied().unwrap_or(0.0)).sum::<f64>() as i32,
+            is_mine: roster.iter().any(|m| m.planet_id == planet_id),
+        }
+    }).collect();
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.score));
+    for (i, item) in items.iter_mut().enumerate() {
+        item.rank = i + 1;
+    }
+
+    Ok(Json(json!(items)))
+}
@@ -1,17 +1,44 @@
 use axum::{Json, http::StatusCode, extract::State};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use sea_orm::{EntityTrait, Set, ActiveModelTrait, QueryFilter, ColumnTrait, QueryOrder};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, QueryFilter, ColumnTrait, TransactionTrait};
 use uuid::Uuid;
 use chrono::Utc;
 use bcrypt::{DEFAULT_COST, hash, verify}; // Import bcrypt
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 
-use crate::{AppState, entities::planet};
+use crate::{AppState, game_logic, levels, placement, entities::{planet, universe}};
 
 #[derive(Deserialize)]
 pub struct AuthPayload {
     pub username: String,
     pub password: String, // On reçoit le mot de passe
+    #[serde(default)]
+    pub universe_id: Option<Uuid>, // Inscription : univers rejoint (par défaut le premier)
+}
+
+const TOKEN_DAYS: i64 = 7;
+
+#[derive(Serialize, Deserialize)]
+struct Claims {
+    sub: String, // ID de la planète
+    exp: usize,
+}
+
+// Token signé portant l'ID de la planète (secret lu au démarrage, voir AppState)
+pub fn issue_token(secret: &str, planet_id: Uuid) -> Result<String, (StatusCode, Json<serde_json::Value>)> {
+    let claims = Claims {
+        sub: planet_id.to_string(),
+        exp: (Utc::now() + chrono::Duration::days(TOKEN_DAYS)).timestamp() as usize,
+    };
+    encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes()))
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur sécurité"}))))
+}
+
+// ID de la planète si le token est valide et non expiré
+pub fn verify_token(secret: &str, token: &str) -> Option<Uuid> {
+    let data = decode::<Claims>(token, &DecodingKey::from_secret(secret.as_bytes()), &Validation::default()).ok()?;
+    Uuid::parse_str(&data.claims.sub).ok()
 }
 
 #[derive(Serialize)]
@@ -36,63 +63,75 @@ pub async fn register_handler(
         return Err((StatusCode::CONFLICT, Json(json!({"error": "Ce commandant existe déjà"}))));
     }
 
-    // --- CALCUL DE LA PROCHAINE POSITION LIBRE ---
-    // On cherche la dernière planète créée pour savoir où se placer
-    let last_planet = planet::Entity::find()
-        .order_by_desc(planet::Column::Id) // Ou trier par date si tu as un champ created_at
-        .one(&state.db)
-        .await
-        .unwrap_or(None);
-
-    let (mut g, mut s, mut p) = (1, 1, 1);
-
-    if let Some(last) = last_planet {
-        g = last.galaxy;
-        s = last.system;
-        p = last.position + 1; // On prend la place suivante
-
-        if p > 15 { // Si le système est plein (15 planètes max)
-            p = 1;
-            s += 1; // On passe au système suivant
-        }
-        if s > 499 { // Limite arbitraire de 499 systèmes par galaxie
-            s = 1;
-            g += 1;
-        }
-    }
-
+    // Univers rejoint : il doit exister et accepter les inscriptions
+    let universe_id = payload.universe_id.unwrap_or_else(|| state.universes.default_id());
+    let universe = match universe::Entity::find_by_id(universe_id).one(&state.db).await {
+        Ok(Some(u)) if u.is_open => game_logic::Universe::from(&u),
+        Ok(Some(_)) => return Err((StatusCode::FORBIDDEN, Json(json!({"error": "Inscriptions fermées dans cet univers"})))),
+        Ok(None) => return Err((StatusCode::NOT_FOUND, Json(json!({"error": "Univers introuvable"})))),
+        Err(_) => return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"})))),
+    };
 
     // 2. Hacher le mot de passe
     let hashed_password = hash(payload.password, DEFAULT_COST)
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur sécurité"}))))?;
 
-    // 3. Créer la planète
+    // 3. Créer la planète sur un emplacement libre (nouvel essai si une inscription
+    //    simultanée a pris la même case entre la recherche et l'insertion)
     let new_id = Uuid::new_v4();
-    let new_planet = planet::ActiveModel {
-        id: Set(new_id),
-        owner_id: Set(Uuid::new_v4()),
-        name: Set(payload.username),
-        password: Set(hashed_password), // On stocke le hash
-
-        // Attribution des coords
-        galaxy: Set(g),
-        system: Set(s),
-        position: Set(p),
-
-
-        metal_mine_level: Set(1),
-        crystal_mine_level: Set(1),
-        deuterium_mine_level: Set(1),
-        last_update: Set(Utc::now().naive_utc()),
-        ..Default::default()
-    };
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let slot = placement::find_free_slot(&state.db, &universe)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))))?;
+        let Some((g, s, p)) = slot else {
+            return Err((StatusCode::SERVICE_UNAVAILABLE, Json(json!({"error": "Univers complet"}))));
+        };
+
+        let mut new_planet = planet::ActiveModel {
+            id: Set(new_id),
+            owner_id: Set(Uuid::new_v4()),
+            universe_id: Set(universe_id),
+            name: Set(payload.username.clone()),
+            password: Set(hashed_password.clone()), // On stocke le hash
+
+            // Attribution des coords
+            galaxy: Set(g),
+            system: Set(s),
+            position: Set(p),
+
+            last_update: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        placement::roll_traits(&mut new_planet, p);
+
+        // Planète et mines de départ écrites ensemble : pas de colonie sans bâtiments
+        let txn = state.db
+            .begin()
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))))?;
+        match new_planet.insert(&txn).await {
+            Ok(_) => {}
+            Err(e) if placement::is_slot_taken(&e) && attempt < placement::MAX_ATTEMPTS => continue,
+            Err(_) => return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Impossible de créer la colonie"})))),
+        }
 
-    new_planet.insert(&state.db).await
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Impossible de créer la colonie"}))))?;
+        // Mines de départ au niveau 1
+        for building_type in ["metal", "crystal", "deuterium"] {
+            levels::set_building(&txn, new_id, building_type, 1)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Impossible de créer la colonie"}))))?;
+        }
+        txn.commit()
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Impossible de créer la colonie"}))))?;
+        break;
+    }
 
-    // 4. Token (Simplifié : ID de la planète)
+    // 4. Token signé
     Ok(Json(AuthResponse {
-        token: "fake-jwt-token".to_string(),
+        token: issue_token(&state.jwt_secret, new_id)?,
         planet_id: new_id.to_string(),
     }))
 }
@@ -120,7 +159,7 @@ pub async fn login_handler(
 
     // 3. Succès
     Ok(Json(AuthResponse {
-        token: "fake-jwt-token".to_string(),
+        token: issue_token(&state.jwt_secret, planet.id)?,
         planet_id: planet.id.to_string(),
     }))
 }
\ No newline at end of file
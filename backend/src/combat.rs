@@ -1,141 +1,121 @@
 use serde::{Deserialize, Serialize};
 use rand::Rng;
 
+use crate::game_logic::{self, Squadron};
+
 // --- STRUCTURES DE DONNÉES ---
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CombatReport {
     pub log: Vec<String>,       // Le journal du combat (ex: "Tour 1: 500 dégâts infligés")
-    pub winner: String,         // "player", "pirates", or "draw"
+    pub winner: String,         // "player", "pirates", "aliens" or "draw"
     pub loot_metal: f64,        // Gain en cas de victoire
-    pub remaining_hunters: i32, // Vaisseaux restants après le combat
-    pub remaining_cruisers: i32,
-    pub remaining_recyclers: i32,
+    pub losses: Squadron,       // Vaisseaux du joueur détruits, tous types confondus
 }
 
-#[derive(Debug, Clone)]
-struct Fleet {
-    hunters: i32,
-    cruisers: i32,
-    recyclers: i32,
+// Adversaires rencontrés en expédition
+#[derive(Debug, Clone, Copy)]
+pub enum Hostile {
+    Pirates,
+    Aliens,
 }
 
-impl Fleet {
-    // Calcul de la puissance de feu totale
-    fn get_total_attack(&self) -> f64 {
-        (self.hunters as f64 * 50.0) +      // Stats du Frontend: 50 Atk
-        (self.cruisers as f64 * 400.0) +    // Stats du Frontend: 400 Atk
-        (self.recyclers as f64 * 1.0)       // Stats du Frontend: 1 Atk
+impl Hostile {
+    fn name(&self) -> &'static str {
+        match self {
+            Hostile::Pirates => "pirates",
+            Hostile::Aliens => "aliens",
+        }
     }
 
-    // Calcul des points de vie totaux (Structure + Bouclier)
-    fn get_total_defense(&self) -> f64 {
-        (self.hunters as f64 * 400.0) +     // Stats du Frontend: 400 Def
-        (self.cruisers as f64 * 2700.0) +   // Stats du Frontend: 2700 Def
-        (self.recyclers as f64 * 1600.0)    // Stats du Frontend: 1600 Def
+    // Force de l'adversaire par rapport à la flotte du joueur
+    fn scaling_range(&self) -> std::ops::Range<f64> {
+        match self {
+            Hostile::Pirates => 0.5..1.1, // Mal équipés, pour que ce soit risqué sans être injuste
+            Hostile::Aliens => 0.9..1.6,  // Plus rares mais bien plus dangereux
+        }
     }
 
-    // Appliquer les dégâts : On réduit le nombre de vaisseaux au prorata des dégâts reçus
-    fn take_damage(&mut self, damage: f64) {
-        let total_def = self.get_total_defense();
-        if total_def <= 0.0 { return; }
-
-        // Si la flotte prend 1000 dégâts sur 10000 PV, elle perd 10% de ses vaisseaux
-        let loss_ratio = damage / total_def; 
-        
-        // On applique le ratio, mais on s'assure qu'au moins 1 vaisseau survit si le ratio n'est pas 100%
-        // Sauf si les dégâts sont massifs.
-        
-        self.hunters = (self.hunters as f64 * (1.0 - loss_ratio)).floor() as i32;
-        self.cruisers = (self.cruisers as f64 * (1.0 - loss_ratio)).floor() as i32;
-        self.recyclers = (self.recyclers as f64 * (1.0 - loss_ratio)).floor() as i32;
+    // Vaisseaux alignés par l'adversaire (puissance de feu répartie à parts égales)
+    fn ship_types(&self) -> [(&'static str, &'static str); 2] {
+        match self {
+            Hostile::Pirates => [("light_hunter", "Chasseurs"), ("cruiser", "Croiseurs")],
+            Hostile::Aliens => [("heavy_hunter", "Chasseurs Lourds"), ("battleship", "Vaisseaux de Bataille")],
+        }
     }
+}
 
-    fn is_destroyed(&self) -> bool {
-        self.hunters <= 0 && self.cruisers <= 0 && self.recyclers <= 0
+// Flotte hostile dont la puissance de feu suit celle du joueur
+fn generate_hostiles(hostile: Hostile, firepower: f64, scaling_factor: f64) -> Squadron {
+    let types = hostile.ship_types();
+    let share = firepower * scaling_factor / types.len() as f64;
+
+    let mut fleet: Squadron = types
+        .iter()
+        .filter_map(|(t, _)| {
+            let attack = game_logic::get_combat_stats(t)?.attack;
+            Some((t.to_string(), (share / attack).round() as i32))
+        })
+        .filter(|(_, qty)| *qty > 0)
+        .collect();
+
+    // Petit bonus aléatoire pour ne pas avoir 0 vaisseaux si le joueur envoie une flotte désarmée
+    if fleet.is_empty() {
+        fleet.insert(types[0].0.to_string(), rand::thread_rng().gen_range(1..3));
     }
+    fleet
 }
 
 // --- MOTEUR DE COMBAT PRINCIPAL ---
 
-pub fn resolve_expedition_combat(
-    p_hunters: i32, 
-    p_cruisers: i32, 
-    p_recyclers: i32
-) -> CombatReport {
+pub fn resolve_expedition_combat(ships: &Squadron, hostile: Hostile) -> CombatReport {
     let mut rng = rand::thread_rng();
     let mut logs = Vec::new();
 
-    // 1. Initialisation de la flotte du Joueur
-    let mut player_fleet = Fleet {
-        hunters: p_hunters,
-        cruisers: p_cruisers,
-        recyclers: p_recyclers,
-    };
+    // 1. Génération de la flotte hostile (Scaling selon l'adversaire)
+    let scaling_factor = rng.gen_range(hostile.scaling_range());
+    let hostiles = generate_hostiles(hostile, game_logic::get_firepower(ships), scaling_factor);
 
-    // 2. Génération de la flotte Pirate (Scaling)
-    // Les pirates ont entre 50% et 110% de la force du joueur pour que ce soit risqué
-    let scaling_factor = rng.gen_range(0.5..1.1); 
-    
-    let mut pirate_fleet = Fleet {
-        hunters: (p_hunters as f64 * scaling_factor).ceil() as i32,
-        cruisers: (p_cruisers as f64 * scaling_factor).ceil() as i32,
-        recyclers: 0, // Les pirates n'utilisent pas de recycleurs
+    let label = match hostile {
+        Hostile::Pirates => "Pirate",
+        Hostile::Aliens => "Alien",
     };
-    
-    // Ajout d'un petit bonus pirate aléatoire pour ne pas avoir 0 vaisseaux si le joueur envoie 1 seul chasseur
-    if pirate_fleet.is_destroyed() {
-        pirate_fleet.hunters = rng.gen_range(1..3);
-    }
-
-    logs.push(format!("ALERTE : Flotte Pirate interceptée ! (Force estimée: {:.0}%)", scaling_factor * 100.0));
-    logs.push(format!("HOSTILES : {} Chasseurs, {} Croiseurs", pirate_fleet.hunters, pirate_fleet.cruisers));
-
-    // 3. Boucle de Combat (Max 6 Tours)
-    let mut round = 1;
-    let mut winner = "draw".to_string();
-
-    while round <= 6 {
-        // Puissance de feu pour ce tour
-        let player_dmg = player_fleet.get_total_attack();
-        let pirate_dmg = pirate_fleet.get_total_attack();
-
-        // Application des dégâts simultanés
-        pirate_fleet.take_damage(player_dmg);
-        player_fleet.take_damage(pirate_dmg);
-
-        logs.push(format!(
-            "TOUR {}: Nous infligeons {:.0} dmg. Pirates ripostent avec {:.0} dmg.",
-            round, player_dmg, pirate_dmg
-        ));
-
-        // Vérification des conditions de victoire
-        if player_fleet.is_destroyed() && pirate_fleet.is_destroyed() {
-            winner = "draw".to_string();
-            logs.push("DESTRUCTION MUTUELLE : Aucune flotte n'a survécu.".to_string());
-            break;
-        } else if pirate_fleet.is_destroyed() {
-            winner = "player".to_string();
-            logs.push("VICTOIRE : La flotte pirate a été annihilée.".to_string());
-            break;
-        } else if player_fleet.is_destroyed() {
-            winner = "pirates".to_string();
+    logs.push(format!("ALERTE : Flotte {} interceptée ! (Force estimée: {:.0}%)", label, scaling_factor * 100.0));
+    let composition: Vec<String> = hostile
+        .ship_types()
+        .iter()
+        .map(|(t, name)| format!("{} {}", hostiles.get(*t).copied().unwrap_or(0), name))
+        .collect();
+    logs.push(format!("HOSTILES : {}", composition.join(", ")));
+
+    // 2. Bataille (Max 6 Tours), avec le même moteur que les attaques de planètes
+    let battle = game_logic::fight(ships, &hostiles);
+    logs.extend(battle.log);
+
+    let winner = match (battle.attacker_alive, battle.defender_alive) {
+        (true, false) => {
+            logs.push(format!("VICTOIRE : La flotte {} a été annihilée.", label.to_lowercase()));
+            "player".to_string()
+        }
+        (false, true) => {
             logs.push("DÉFAITE : Contact perdu avec notre flotte.".to_string());
-            break;
+            hostile.name().to_string()
         }
+        (false, false) => {
+            logs.push("DESTRUCTION MUTUELLE : Aucune flotte n'a survécu.".to_string());
+            "draw".to_string()
+        }
+        (true, true) => {
+            logs.push("FUITE : Le combat s'éternise, les flottes se désengagent.".to_string());
+            "draw".to_string()
+        }
+    };
 
-        round += 1;
-    }
-
-    if round > 6 {
-        logs.push("FUITE : Le combat s'éternise, les flottes se désengagent.".to_string());
-    }
-
-    // 4. Calcul du butin (Seulement si victoire)
+    // 3. Calcul du butin (Seulement si victoire)
     let mut loot = 0.0;
     if winner == "player" {
-        // Butin basé sur la force des pirates vaincus
-        loot = (pirate_fleet.get_total_attack() * 10.0) + 5000.0;
+        // Butin basé sur la force des hostiles vaincus
+        loot = (game_logic::get_firepower(&hostiles) * 10.0) + 5000.0;
         logs.push(format!("EPAVE FOUILLÉE : +{:.0} Métal récupéré.", loot));
     }
 
@@ -143,8 +123,6 @@ pub fn resolve_expedition_combat(
         log: logs,
         winner,
         loot_metal: loot,
-        remaining_hunters: player_fleet.hunters,
-        remaining_cruisers: player_fleet.cruisers,
-        remaining_recyclers: player_fleet.recyclers,
+        losses: battle.att_lost,
     }
-}
\ No newline at end of file
+}
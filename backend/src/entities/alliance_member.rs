@@ -0,0 +1,17 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "alliance_member")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub planet_id: Uuid,
+    pub alliance_id: Uuid,
+    pub rank_id: Option<Uuid>, // None = simple membre (le fondateur a toutes les permissions)
+    pub joined_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
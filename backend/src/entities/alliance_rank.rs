@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "alliance_rank")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub alliance_id: Uuid,
+    pub name: String,
+
+    // Permissions
+    #[sea_orm(default_value = false)]
+    pub can_accept: bool,
+    #[sea_orm(default_value = false)]
+    pub can_kick: bool,
+    #[sea_orm(default_value = false)]
+    pub can_manage_ranks: bool,
+    #[sea_orm(default_value = false)]
+    pub can_circular: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,52 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "fleet")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub planet_id: Uuid,      // Planète d'origine
+    pub mission_type: String, // "expedition", "attack", "transport", "deploy", "defend"
+    pub status: String,       // "outbound", "returning"
+
+    // Destination
+    pub target_planet_id: Option<Uuid>, // Planète visée (attaques)
+    pub target_galaxy: i32,
+    pub target_system: i32,
+    pub target_position: i32,
+
+    // Attaque groupée (AG) : id de la flotte meneuse, partagé par tous les participants
+    pub acs_group_id: Option<Uuid>,
+
+    // Composition (JSON : { "light_hunter": 10, "cruiser": 2 })
+    #[sea_orm(column_type = "Text")]
+    pub ships: String,
+
+    // Soute
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub cargo_metal: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub cargo_crystal: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub cargo_deuterium: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub cargo_dark_matter: f64,
+
+    // Horaires
+    pub departure_time: DateTime,
+    pub arrival_time: DateTime,
+    #[sea_orm(default_value = 0)]
+    pub hold_hours: i32,    // Durée de stationnement choisie (heures de jeu)
+    pub hold_end: DateTime, // Fin du stationnement sur place (= arrival_time si pas d'attente)
+    pub return_time: DateTime,
+
+    // Rapport de mission, livré au joueur au retour
+    #[sea_orm(column_type = "Text", nullable)]
+    pub report: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
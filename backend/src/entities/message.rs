@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "message")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub recipient_id: Uuid,
+    pub sender_id: Option<Uuid>, // None = message système
+    pub sender_name: String,
+    pub folder: String, // "personal", "combat", "espionage", "expedition", "alliance", "system"
+    pub subject: String,
+    #[sea_orm(column_type = "Text")]
+    pub body: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub report: Option<String>, // Rapport JSON joint (combat, espionnage, expédition)
+    #[sea_orm(default_value = false)]
+    pub is_read: bool,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,7 +1,17 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
-pub mod prelude ;
-
 pub mod planet ;
-pub mod user ;
-pub mod combat_log;
\ No newline at end of file
+pub mod combat_log;
+pub mod fleet;
+pub mod ranking;
+pub mod alliance;
+pub mod alliance_rank;
+pub mod alliance_member;
+pub mod alliance_application;
+pub mod message;
+pub mod notification;
+pub mod resource_ledger;
+pub mod universe;
+pub mod planet_unit;
+pub mod planet_building;
+pub mod player_research;
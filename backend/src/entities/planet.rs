@@ -7,17 +7,12 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
     pub owner_id: Uuid,
+    pub universe_id: Uuid,
     pub name: String,
     #[serde(skip)] // IMPORTANT : Ne jamais renvoyer le hash du mdp au frontend
     pub password: String,
 
-    // Niveaux Mines
-    #[sea_orm(default_value = 1)]
-    pub metal_mine_level: i32,
-    #[sea_orm(default_value = 1)]
-    pub crystal_mine_level: i32,
-    #[sea_orm(default_value = 1)]
-    pub deuterium_mine_level: i32,
+    // Niveaux des bâtiments : voir planet_building (recherches : player_research)
 
     // Ressources
     #[sea_orm(default_value = 0.0)]
@@ -26,6 +21,8 @@ pub struct Model {
     pub crystal_amount: f64,
     #[sea_orm(default_value = 0.0)]
     pub deuterium_amount: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub dark_matter_amount: f64,
 
     // Timers
     pub last_update: DateTime,
@@ -36,31 +33,7 @@ pub struct Model {
     #[sea_orm(default_value = 0)]
     pub pending_fleet_count: i32,
     
-    // Flotte
-    #[sea_orm(default_value = 0)]
-    pub light_hunter_count: i32,
-    #[sea_orm(default_value = 0)]
-    pub cruiser_count: i32,
-    #[sea_orm(default_value = 0)]
-    pub recycler_count: i32,
-
-    // AJOUT
-    #[sea_orm(default_value = 0)]
-    pub spy_probe_count: i32, // <--- ICI
-
-    // Tech
-    #[sea_orm(default_value = 0)]
-    pub energy_tech_level: i32,
-    #[sea_orm(default_value = 0)]
-    pub research_lab_level: i32,
-    #[sea_orm(default_value = 0)]
-    pub laser_battery_level: i32,
-
-    // DÉFENSES (Nouveaux champs)
-    #[sea_orm(default_value = 0)]
-    pub missile_launcher_count: i32,
-    #[sea_orm(default_value = 0)]
-    pub plasma_turret_count: i32,
+    // Vaisseaux et défenses : voir planet_unit
 
 // NOUVEAUX CHAMPS DÉBRIS
     #[sea_orm(column_type = "Double", default_value = 0.0)]
@@ -75,19 +48,49 @@ pub struct Model {
     pub system: i32,
     #[sea_orm(default_value = 1)]
     pub position: i32,
-    
-    // AJOUT
-    #[sea_orm(default_value = 0)]
-    pub espionage_tech_level: i32, // <--- ICI
 
+    // Caractéristiques tirées à la création selon la position
+    #[sea_orm(default_value = 0)]
+    pub diameter: i32, // km
+    #[sea_orm(default_value = 0)]
+    pub fields_max: i32, // Cases constructibles (1 par niveau de bâtiment)
+    #[sea_orm(default_value = 0)]
+    pub temperature_min: i32, // °C
+    #[sea_orm(default_value = 0)]
+    pub temperature_max: i32, // Plus la planète est froide, plus le deutérium rend
+    
     // Expédition
     pub expedition_end: Option<DateTime>,
 
-    // --- NOUVEAU CHAMP ---
-    pub unread_report: Option<String>,
+    // Repaire pirate contrôlé par le serveur
+    #[sea_orm(default_value = false)]
+    pub is_npc: bool,
+
+    // Mode vacances : production gelée, planète intouchable
+    pub vacation_start: Option<DateTime>,
+    pub vacation_min_end: Option<DateTime>, // Sortie impossible avant cette date
+
+    // Points de classement (1 point = 1000 ressources dépensées)
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub points_economy: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub points_research: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub points_military_built: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub points_military_destroyed: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub points_military_lost: f64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
-impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    // Mode vacances : posé et levé sur toutes les planètes du joueur à la fois
+    pub fn is_on_vacation(&self) -> bool {
+        self.vacation_start.is_some()
+    }
+}
\ No newline at end of file
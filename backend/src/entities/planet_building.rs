@@ -0,0 +1,18 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "planet_building")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub planet_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub building_type: String, // levels::BUILDING_TYPES
+    #[sea_orm(default_value = 0)]
+    pub level: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,18 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "player_research")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub owner_id: Uuid, // planet.owner_id : la recherche vaut pour toutes les planètes du joueur
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub research_type: String, // levels::RESEARCH_TYPES
+    #[sea_orm(default_value = 0)]
+    pub level: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
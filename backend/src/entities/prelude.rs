@@ -1,4 +0,0 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
-pub use super :: planet :: Entity as Planet ;
-pub use super :: user :: Entity as User ;
\ No newline at end of file
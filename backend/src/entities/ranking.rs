@@ -0,0 +1,22 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "ranking")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub planet_id: Uuid,
+    pub planet_name: String,
+    pub universe_id: Uuid,
+    pub category: String, // "total", "economy", "research", "military", ...
+    pub rank: i32,
+    #[sea_orm(column_type = "Double")]
+    pub score: f64,
+    pub snapshot_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "resource_ledger")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub planet_id: Uuid,
+
+    // Variation signée de chaque ressource
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub metal: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub crystal: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub deuterium: f64,
+    #[sea_orm(column_type = "Double", default_value = 0.0)]
+    pub dark_matter: f64,
+
+    pub reason: String,          // Voir ledger::REASONS
+    pub related_id: Option<Uuid>, // Flotte ou planète à l'origine du mouvement
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "universe")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub name: String,
+
+    // Vitesses
+    #[sea_orm(column_type = "Double")]
+    pub economy_speed: f64,
+    #[sea_orm(column_type = "Double")]
+    pub research_speed: f64,
+    #[sea_orm(column_type = "Double")]
+    pub fleet_speed: f64,
+    #[sea_orm(column_type = "Double")]
+    pub expedition_speed: f64,
+
+    // Taille
+    pub galaxies: i32,
+    pub systems_per_galaxy: i32,
+
+    // Champs de débris
+    #[sea_orm(column_type = "Double")]
+    pub debris_ratio: f64,
+    #[sea_orm(column_type = "Double")]
+    pub defense_debris_ratio: f64,
+
+    #[sea_orm(default_value = true)]
+    pub is_open: bool, // Inscriptions ouvertes
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,171 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{NaiveDateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use sea_orm::EntityTrait;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::entities::planet;
+use crate::levels::{self, Levels};
+use crate::{auth, game_logic, AppState};
+
+const BUS_CAPACITY: usize = 1024;
+const TICK_SECONDS: u64 = 5; // Fréquence des mises à jour de ressources
+
+// Événements poussés aux clients connectés
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    Resources { metal: f64, crystal: f64, deuterium: f64 },
+    ConstructionComplete { construction_type: String },
+    ShipyardComplete { ship_type: String, count: i32 },
+    IncomingFleet { fleet_id: Uuid, origin_name: String, mission_type: String, arrival_time: NaiveDateTime },
+    Notification { kind: String, payload: Value },
+    Message { folder: String, sender_name: String, subject: String },
+}
+
+type Pending = Arc<Mutex<Vec<(Uuid, GameEvent)>>>;
+
+// Bus de diffusion interne : chaque événement est adressé à une planète
+#[derive(Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<(Uuid, GameEvent)>,
+    pending: Option<Pending>, // Événements retenus jusqu'au commit
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(BUS_CAPACITY);
+        EventBus { tx, pending: None }
+    }
+
+    // Copie qui retient les événements publiés dans une transaction : flush() après le commit.
+    // En cas de rollback, la copie est abandonnée et les clients ne voient rien passer.
+    pub fn deferred(&self) -> Self {
+        EventBus { tx: self.tx.clone(), pending: Some(Arc::default()) }
+    }
+
+    pub fn flush(&self) {
+        if let Some(pending) = &self.pending {
+            for (planet_id, event) in pending.lock().unwrap().drain(..) {
+                let _ = self.tx.send((planet_id, event));
+            }
+        }
+    }
+
+    // Sans abonné, l'événement est simplement perdu
+    pub fn publish(&self, planet_id: Uuid, event: GameEvent) {
+        match &self.pending {
+            Some(pending) => pending.lock().unwrap().push((planet_id, event)),
+            None => {
+                let _ = self.tx.send((planet_id, event));
+            }
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<(Uuid, GameEvent)> {
+        self.tx.subscribe()
+    }
+}
+
+// --- WEBSOCKET ---
+
+// GET /ws?token=... (le navigateur ne peut pas envoyer d'en-tête Authorization)
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let Some(planet_id) = params.get("token").and_then(|t| auth::verify_token(&state.jwt_secret, t)) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    ws.on_upgrade(move |socket| run_socket(socket, state, planet_id))
+}
+
+async fn run_socket(socket: WebSocket, state: AppState, planet_id: Uuid) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut events = state.events.subscribe();
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(TICK_SECONDS));
+    let mut watch = Completions::default();
+
+    loop {
+        let outgoing = tokio::select! {
+            event = events.recv() => match event {
+                Ok((target, event)) if target == planet_id => vec![event],
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = ticker.tick() => match planet::Entity::find_by_id(planet_id).one(&state.db).await {
+                Ok(Some(p)) => match (levels::load(&state.db, &p).await, state.universes.get(&state.db, p.universe_id).await) {
+                    (Ok(lv), Ok(Some(universe))) => watch.tick(&p, &lv, &universe),
+                    _ => continue,
+                },
+                _ => continue,
+            },
+            incoming = receiver.next() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue, // Le client n'envoie rien d'utile
+            },
+        };
+
+        for event in outgoing {
+            let text = serde_json::to_string(&event).unwrap_or_default();
+            if sender.send(Message::Text(text)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+// Fin des chantiers déjà signalée, pour ne l'annoncer qu'une fois
+#[derive(Default)]
+struct Completions {
+    construction_done: Option<NaiveDateTime>,
+    shipyard_done: Option<NaiveDateTime>,
+}
+
+impl Completions {
+    fn tick(&mut self, p: &planet::Model, lv: &Levels, universe: &game_logic::Universe) -> Vec<GameEvent> {
+        let now = Utc::now().naive_utc();
+        let mut out = vec![resources_event(p, lv, universe)];
+
+        if let (Some(end), Some(kind)) = (p.construction_end, &p.construction_type) {
+            if now >= end && self.construction_done != Some(end) {
+                self.construction_done = Some(end);
+                out.push(GameEvent::ConstructionComplete { construction_type: kind.clone() });
+            }
+        }
+        if let (Some(end), Some(kind)) = (p.shipyard_construction_end, &p.pending_fleet_type) {
+            if now >= end && self.shipyard_done != Some(end) {
+                self.shipyard_done = Some(end);
+                out.push(GameEvent::ShipyardComplete { ship_type: kind.clone(), count: p.pending_fleet_count });
+            }
+        }
+
+        out
+    }
+}
+
+// Stock projeté à l'instant présent (la production n'est écrite en base qu'à la consultation)
+fn resources_event(p: &planet::Model, lv: &Levels, universe: &game_logic::Universe) -> GameEvent {
+    if p.is_on_vacation() {
+        return GameEvent::Resources { metal: p.metal_amount, crystal: p.crystal_amount, deuterium: p.deuterium_amount };
+    }
+    GameEvent::Resources {
+        metal: game_logic::calculate_resources(universe, game_logic::ResourceType::Metal, levels::get(lv, "metal"), p.temperature_max, p.metal_amount, p.last_update),
+        crystal: game_logic::calculate_resources(universe, game_logic::ResourceType::Crystal, levels::get(lv, "crystal"), p.temperature_max, p.crystal_amount, p.last_update),
+        deuterium: game_logic::calculate_resources(universe, game_logic::ResourceType::Deuterium, levels::get(lv, "deuterium"), p.temperature_max, p.deuterium_amount, p.last_update),
+    }
+}
@@ -0,0 +1,189 @@
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::combat::{self, Hostile};
+use crate::game_logic::{self, Cost};
+
+// --- TABLE DES ISSUES ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpeditionOutcome {
+    Nothing,
+    Metal,
+    Crystal,
+    Deuterium,
+    Ships,
+    DarkMatter,
+    Pirates,
+    Aliens,
+    Delay,
+    EarlyReturn,
+    BlackHole,
+}
+
+// Poids relatifs de chaque issue (total = 100)
+pub const OUTCOME_TABLE: &[(ExpeditionOutcome, u32)] = &[
+    (ExpeditionOutcome::Nothing, 24),
+    (ExpeditionOutcome::Metal, 18),
+    (ExpeditionOutcome::Crystal, 12),
+    (ExpeditionOutcome::Deuterium, 8),
+    (ExpeditionOutcome::Ships, 9),
+    (ExpeditionOutcome::DarkMatter, 5),
+    (ExpeditionOutcome::Pirates, 9),
+    (ExpeditionOutcome::Aliens, 4),
+    (ExpeditionOutcome::Delay, 6),
+    (ExpeditionOutcome::EarlyReturn, 4),
+    (ExpeditionOutcome::BlackHole, 1),
+];
+
+pub fn roll_outcome() -> ExpeditionOutcome {
+    let total: u32 = OUTCOME_TABLE.iter().map(|(_, weight)| weight).sum();
+    let mut roll = rand::thread_rng().gen_range(0..total);
+
+    for (outcome, weight) in OUTCOME_TABLE {
+        if roll < *weight {
+            return *outcome;
+        }
+        roll -= weight;
+    }
+    ExpeditionOutcome::Nothing
+}
+
+// --- RÉSOLUTION ---
+
+pub struct ExpeditionResult {
+    pub outcome: ExpeditionOutcome,
+    pub winner: String, // "player", "pirates", "aliens", "draw"
+    pub log: Vec<String>,
+    pub ships: HashMap<String, i32>, // Flotte qui rentre (survivants + vaisseaux trouvés)
+    pub losses: HashMap<String, i32>,
+    pub found_ships: HashMap<String, i32>,
+    pub resources: Cost,
+    pub dark_matter: f64,
+    pub return_factor: f64, // Multiplicateur du temps de retour (1.0 = normal)
+}
+
+impl ExpeditionResult {
+    pub fn ships_lost(&self) -> i32 {
+        self.losses.values().sum()
+    }
+}
+
+pub fn resolve_expedition(ships: &HashMap<String, i32>, hold_hours: i32, hyperspace_level: i32) -> ExpeditionResult {
+    let outcome = roll_outcome();
+    resolve_outcome(outcome, ships, hold_hours, hyperspace_level)
+}
+
+pub fn resolve_outcome(outcome: ExpeditionOutcome, ships: &HashMap<String, i32>, hold_hours: i32, hyperspace_level: i32) -> ExpeditionResult {
+    let mut rng = rand::thread_rng();
+    let mut log = vec![format!("SCAN : Secteur inexploré, stationnement de {}h.", hold_hours)];
+    let mut winner = "player".to_string();
+    let mut survivors = ships.clone();
+    let mut found_ships = HashMap::new();
+    let mut resources = Cost { metal: 0.0, crystal: 0.0, deuterium: 0.0 };
+    let mut dark_matter = 0.0;
+    let mut return_factor = 1.0;
+
+    // Les trouvailles sont limitées par la soute de la flotte
+    let cargo: f64 = ships
+        .iter()
+        .map(|(ship_type, count)| game_logic::get_cargo_capacity(ship_type, *count, hyperspace_level))
+        .sum();
+    let hours = hold_hours as f64;
+
+    match outcome {
+        ExpeditionOutcome::Nothing => {
+            log.push("RAS : Le secteur est vide, la flotte rentre bredouille.".to_string());
+        }
+        ExpeditionOutcome::Metal => {
+            resources.metal = f64::min(cargo, 40000.0 * hours * rng.gen_range(0.5..1.0));
+            log.push(format!("DECOUVERTE : Gisement de métal (+{:.0} Métal).", resources.metal));
+        }
+        ExpeditionOutcome::Crystal => {
+            resources.crystal = f64::min(cargo, 20000.0 * hours * rng.gen_range(0.5..1.0));
+            log.push(format!("DECOUVERTE : Astéroïde cristallin (+{:.0} Cristal).", resources.crystal));
+        }
+        ExpeditionOutcome::Deuterium => {
+            resources.deuterium = f64::min(cargo, 10000.0 * hours * rng.gen_range(0.5..1.0));
+            log.push(format!("DECOUVERTE : Nuage de gaz (+{:.0} Deutérium).", resources.deuterium));
+        }
+        ExpeditionOutcome::Ships => {
+            // Épaves réparables, proportionnelles à la taille de la flotte
+            let fleet_size: i32 = ships.values().sum();
+            let hunters = std::cmp::max(1, (fleet_size as f64 * rng.gen_range(0.05..0.2)) as i32);
+            let cruisers = hunters / 5;
+            found_ships.insert("light_hunter".to_string(), hunters);
+            if cruisers > 0 {
+                found_ships.insert("cruiser".to_string(), cruisers);
+            }
+            log.push(format!("DECOUVERTE : Flotte abandonnée ({} Chasseurs, {} Croiseurs).", hunters, cruisers));
+        }
+        ExpeditionOutcome::DarkMatter => {
+            dark_matter = (rng.gen_range(100.0..500.0) * hours).floor();
+            log.push(format!("DECOUVERTE : Anomalie stellaire (+{:.0} Matière Noire).", dark_matter));
+        }
+        ExpeditionOutcome::Pirates | ExpeditionOutcome::Aliens => {
+            let hostile = if outcome == ExpeditionOutcome::Pirates { Hostile::Pirates } else { Hostile::Aliens };
+            log.push("⚠️ RADAR : Signature hostile détectée.".to_string());
+
+            let report = combat::resolve_expedition_combat(ships, hostile);
+            log.extend(report.log);
+
+            // Pertes appliquées à chaque type engagé, cargos et sondes compris
+            for (ship_type, lost) in &report.losses {
+                if let Some(qty) = survivors.get_mut(ship_type) {
+                    *qty -= lost;
+                }
+            }
+
+            resources.metal = report.loot_metal;
+            winner = report.winner;
+        }
+        ExpeditionOutcome::Delay => {
+            return_factor = rng.gen_range(1.5..3.0);
+            log.push("ANOMALIE : Tempête ionique, le retour de la flotte est retardé.".to_string());
+        }
+        ExpeditionOutcome::EarlyReturn => {
+            return_factor = 0.5;
+            log.push("COURANT : Un couloir gravitationnel accélère le retour de la flotte.".to_string());
+        }
+        ExpeditionOutcome::BlackHole => {
+            for value in survivors.values_mut() {
+                *value = 0;
+            }
+            winner = "black_hole".to_string();
+            log.push("CATASTROPHE : Un trou noir a englouti la flotte. Contact perdu.".to_string());
+        }
+    }
+
+    let mut losses = HashMap::new();
+    for (ship_type, sent) in ships {
+        let lost = sent - count(&survivors, ship_type);
+        if lost > 0 {
+            losses.insert(ship_type.clone(), lost);
+        }
+    }
+
+    for (ship_type, qty) in &found_ships {
+        *survivors.entry(ship_type.clone()).or_insert(0) += qty;
+    }
+    survivors.retain(|_, qty| *qty > 0);
+
+    ExpeditionResult {
+        outcome,
+        winner,
+        log,
+        ships: survivors,
+        losses,
+        found_ships,
+        resources,
+        dark_matter,
+        return_factor,
+    }
+}
+
+fn count(ships: &HashMap<String, i32>, ship_type: &str) -> i32 {
+    ships.get(ship_type).copied().unwrap_or(0)
+}
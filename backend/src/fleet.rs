@@ -0,0 +1,1030 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    IntoActiveModel, ModelTrait, QueryFilter, QueryOrder, QuerySelect, Select, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{combat_log, fleet, planet};
+use crate::events::{EventBus, GameEvent};
+use crate::notification::{self, NotificationKind};
+use crate::{alliance, expedition, ledger, levels, locks, messaging, units};
+use crate::game_logic::{self, PvpReport, Squadron};
+use crate::universe::Universes;
+use crate::{ranking, AppState};
+
+// Vaisseaux pouvant quitter la planète
+pub const SHIP_TYPES: [&str; 10] = [
+    "light_hunter", "heavy_hunter", "cruiser", "battleship", "bomber", "destroyer",
+    "recycler", "spy_probe", "small_cargo", "large_cargo",
+];
+
+// --- COMPOSITION ---
+
+pub fn parse_ships(raw: &str) -> HashMap<String, i32> {
+    serde_json::from_str(raw).unwrap_or_default()
+}
+
+// Vitesse du vaisseau le plus lent de la flotte
+pub fn slowest_speed(ships: &HashMap<String, i32>) -> i32 {
+    ships
+        .iter()
+        .filter(|(_, qty)| **qty > 0)
+        .filter_map(|(ship_type, _)| game_logic::get_ship_stats(ship_type))
+        .map(|s| s.speed)
+        .min()
+        .unwrap_or(1)
+}
+
+// --- TRAITEMENT DES MOUVEMENTS ---
+
+// Résout toutes les flottes arrivées à destination puis celles rentrées au port
+pub async fn process_fleets(db: &DatabaseConnection, bus: &EventBus, universes: &Universes) -> Result<(), DbErr> {
+    let now = Utc::now().naive_utc();
+
+    let arrived = fleet::Entity::find()
+        .filter(fleet::Column::Status.eq("outbound"))
+        .filter(fleet::Column::HoldEnd.lte(now))
+        .all(db)
+        .await?;
+
+    // Une flotte en échec est journalisée puis retentée au passage suivant,
+    // sans bloquer les autres ni faire échouer la requête du joueur qui a déclenché le traitement
+    for f in arrived {
+        if let Err(e) = resolve_arrival(db, bus, universes, &f, now).await {
+            println!("⚠️ Flotte {} non résolue : {}", f.id, e);
+        }
+    }
+
+    let returned = fleet::Entity::find()
+        .filter(fleet::Column::Status.eq("returning"))
+        .filter(fleet::Column::ReturnTime.lte(now))
+        .all(db)
+        .await?;
+
+    for f in returned {
+        if let Err(e) = resolve_return(db, bus, &f).await {
+            println!("⚠️ Retour de la flotte {} non traité : {}", f.id, e);
+        }
+    }
+
+    Ok(())
+}
+
+// Chaque flotte est résolue dans sa propre transaction, après verrouillage et relecture :
+// une autre requête a pu la traiter entre la recherche et le verrou
+async fn resolve_arrival(db: &DatabaseConnection, bus: &EventBus, universes: &Universes, f: &fleet::Model, now: NaiveDateTime) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    let planet_ids = involved_planets(&txn, f, now).await?;
+    locks::lock_planets(&txn, &planet_ids).await?;
+    let Some(f) = lock_due_fleet(&txn, f, now, &planet_ids).await? else {
+        return Ok(());
+    };
+    let bus = bus.deferred(); // Alertes et rapports annoncés une fois la résolution validée
+    match f.mission_type.as_str() {
+        "expedition" => resolve_expedition_arrival(&txn, f, now).await?,
+        "attack" => resolve_attack_arrival(&txn, &bus, universes, f, now).await?,
+        "transport" => resolve_transport_arrival(&txn, &bus, f, now).await?,
+        "deploy" => resolve_deploy_arrival(&txn, &bus, f, now).await?,
+        "defend" => resolve_defend_end(&txn, f, now).await?,
+        _ => {}
+    }
+    txn.commit().await?;
+    bus.flush();
+    Ok(())
+}
+
+async fn resolve_return(db: &DatabaseConnection, bus: &EventBus, f: &fleet::Model) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    locks::lock_planet(&txn, f.planet_id).await?; // Planète avant flotte, comme partout
+    let Some(f) = locks::lock_fleet(&txn, f.id).await?.filter(|f| f.status == "returning") else {
+        return Ok(());
+    };
+    let bus = bus.deferred();
+    complete_return(&txn, &bus, f).await?;
+    txn.commit().await?;
+    bus.flush();
+    Ok(())
+}
+
+// Planètes touchées par la résolution : origines du groupe, cible et propriétaires des soutiens.
+// Lues sans verrou pour pouvoir les verrouiller avant les flottes ; les soutiens ne peuvent que
+// disparaître d'ici là, seul un ralliement de dernière seconde à l'AG est revérifié ensuite
+async fn involved_planets(db: &impl ConnectionTrait, f: &fleet::Model, now: NaiveDateTime) -> Result<Vec<Uuid>, DbErr> {
+    let mut ids = vec![f.planet_id];
+    ids.extend(f.target_planet_id);
+    if let Some(group_id) = f.acs_group_id {
+        let group = fleet::Entity::find()
+            .filter(fleet::Column::AcsGroupId.eq(group_id))
+            .all(db)
+            .await?;
+        ids.extend(group.iter().map(|m| m.planet_id));
+    }
+    if let (Some(target_id), "attack") = (f.target_planet_id, f.mission_type.as_str()) {
+        let supporters = supporting_fleets(target_id, now).all(db).await?;
+        ids.extend(supporters.iter().map(|s| s.planet_id));
+    }
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
+
+// Verrouille une flotte arrivée (et tout son groupe d'attaque, dans un ordre stable)
+// puis la renvoie si elle est toujours à résoudre. Un membre dont la planète n'a pas été
+// verrouillée au préalable (ralliement pendant la lecture) : résolution remise au passage suivant
+async fn lock_due_fleet(db: &impl ConnectionTrait, f: &fleet::Model, now: NaiveDateTime, planet_ids: &[Uuid]) -> Result<Option<fleet::Model>, DbErr> {
+    let locked = match f.acs_group_id {
+        Some(group_id) => {
+            let group = fleet::Entity::find()
+                .filter(fleet::Column::AcsGroupId.eq(group_id))
+                .order_by_asc(fleet::Column::Id)
+                .lock_exclusive()
+                .all(db)
+                .await?;
+            if !group.iter().all(|m| planet_ids.contains(&m.planet_id)) {
+                return Ok(None);
+            }
+            group.into_iter().find(|l| l.id == f.id)
+        }
+        None => locks::lock_fleet(db, f.id).await?,
+    };
+    Ok(locked.filter(|l| l.status == "outbound" && l.hold_end <= now))
+}
+
+async fn resolve_expedition_arrival(db: &impl ConnectionTrait, f: fleet::Model, now: NaiveDateTime) -> Result<(), DbErr> {
+    let origin = locks::lock_planet(db, f.planet_id).await?;
+    let hyperspace_level = match &origin {
+        Some(p) => levels::get(&levels::load_research(db, p.owner_id).await?, "hyperspace"),
+        None => 0,
+    };
+
+    let ships = parse_ships(&f.ships);
+    let result = expedition::resolve_expedition(&ships, std::cmp::max(1, f.hold_hours), hyperspace_level);
+
+    // Vaisseaux perdus en route
+    let lost_points: f64 = result.losses.iter()
+        .map(|(ship_type, qty)| *qty as f64 * game_logic::get_unit_value(ship_type))
+        .sum::<f64>() / 1000.0;
+    if let (Some(p), true) = (origin, lost_points > 0.0) {
+        let mut origin_active: planet::ActiveModel = p.into();
+        ranking::add_points(&mut origin_active, "military_lost", lost_points);
+        origin_active.update(db).await?;
+    }
+
+    let report = json!({
+        "winner": result.winner,
+        "outcome": result.outcome,
+        "log": result.log,
+        "loot": result.resources.metal + result.resources.crystal + result.resources.deuterium,
+        "resources": result.resources,
+        "dark_matter": result.dark_matter,
+        "found_ships": result.found_ships,
+        "losses": result.losses,
+        "ships_lost": result.ships_lost(),
+        "result": result.winner,
+        "is_expedition": true
+    });
+
+    // Le retour dure autant que l'aller, modulé par les anomalies
+    let flight = f.arrival_time.signed_duration_since(f.departure_time).num_seconds();
+    let back = std::cmp::max(1, (flight as f64 * result.return_factor) as i64);
+    let return_time = if result.ships.is_empty() { now } else { f.hold_end + Duration::seconds(back) };
+
+    let mut active = f.into_active_model();
+    active.status = Set("returning".to_string());
+    active.ships = Set(serde_json::to_string(&result.ships).unwrap());
+    active.cargo_metal = Set(result.resources.metal);
+    active.cargo_crystal = Set(result.resources.crystal);
+    active.cargo_deuterium = Set(result.resources.deuterium);
+    active.cargo_dark_matter = Set(result.dark_matter);
+    active.return_time = Set(return_time);
+    active.report = Set(Some(report.to_string()));
+    active.update(db).await?;
+
+    Ok(())
+}
+
+// --- COMBAT ---
+
+// Applique l'issue d'un combat à la planète défendue : pertes, pillage, débris et rapport.
+// `own_losses` = vaisseaux et défenses perdus par la planète elle-même (hors flottes alliées en soutien).
+// Renvoie les points détruits (valeur des unités perdues par la planète).
+pub async fn apply_defense(
+    db: &impl ConnectionTrait,
+    bus: &EventBus,
+    def_planet: planet::Model,
+    attacker_name: &str,
+    result: &PvpReport,
+    own_losses: &Squadron,
+) -> Result<f64, DbErr> {
+    let units_lost: i32 = own_losses.values().sum();
+    let log_def = combat_log::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        planet_id: Set(def_planet.id),
+        target_name: Set(attacker_name.to_string()),
+        mission_type: Set("defense".to_string()),
+        result: Set(if result.winner == "defender" { "victory".to_string() } else { "defeat".to_string() }),
+        loot_metal: Set(-result.loot.metal),
+        loot_crystal: Set(-result.loot.crystal),
+        ships_lost: Set(units_lost),
+        date: Set(Utc::now().naive_utc()),
+    };
+    log_def.insert(db).await?;
+
+    ledger::record(db, def_planet.id, ledger::Delta::spend(&result.loot), "plundered", None).await?;
+
+    let mut def_active: planet::ActiveModel = def_planet.clone().into();
+    def_active.metal_amount = Set(def_planet.metal_amount - result.loot.metal);
+    def_active.crystal_amount = Set(def_planet.crystal_amount - result.loot.crystal);
+    def_active.deuterium_amount = Set(def_planet.deuterium_amount - result.loot.deuterium);
+
+    units::add_all(db, def_planet.id, own_losses, -1).await?;
+    let lost_points = game_logic::get_units_points(own_losses);
+    ranking::add_points(&mut def_active, "military_lost", lost_points);
+
+    // Débris au-dessus de la planète (défenses détruites comprises selon l'univers)
+    def_active.debris_metal = Set(def_planet.debris_metal + result.debris.metal);
+    def_active.debris_crystal = Set(def_planet.debris_crystal + result.debris.crystal);
+
+    let defender_report = json!({
+        "winner": result.winner, 
+        "log": result.log,
+        "loot": result.loot.metal + result.loot.crystal,
+        "losses": own_losses,
+        "is_defense": true
+    });
+    def_active.update(db).await?;
+
+    if !def_planet.is_npc {
+        notification::push(db, bus, def_planet.id, NotificationKind::Defense, &defender_report).await?;
+        let body = format!(
+            "{} a attaqué votre planète : {}. Pillage : {:.0} ressources, {} unités perdues.",
+            attacker_name,
+            if result.winner == "defender" { "attaque repoussée" } else { "défenses percées" },
+            result.loot.metal + result.loot.crystal,
+            units_lost,
+        );
+        messaging::notify(db, bus, def_planet.id, "combat", &format!("Attaque subie : {}", attacker_name), body, Some(&defender_report)).await?;
+    }
+
+    Ok(lost_points)
+}
+
+// Flottes alliées en soutien sur la planète (arrivées et encore en stationnement)
+fn supporting_fleets(planet_id: Uuid, now: NaiveDateTime) -> Select<fleet::Entity> {
+    fleet::Entity::find()
+        .filter(fleet::Column::MissionType.eq("defend"))
+        .filter(fleet::Column::TargetPlanetId.eq(planet_id))
+        .filter(fleet::Column::Status.eq("outbound"))
+        .filter(fleet::Column::ArrivalTime.lte(now))
+        .filter(fleet::Column::HoldEnd.gt(now))
+}
+
+async fn find_supporting_fleets(db: &impl ConnectionTrait, planet_id: Uuid, now: NaiveDateTime) -> Result<Vec<fleet::Model>, DbErr> {
+    supporting_fleets(planet_id, now)
+        .order_by_asc(fleet::Column::Id)
+        .lock_exclusive() // Pas de rappel pendant le combat
+        .all(db)
+        .await
+}
+
+// Retire les pertes d'une flotte ; renvoie la flotte survivante
+fn remove_losses(mut ships: HashMap<String, i32>, losses: &Squadron) -> HashMap<String, i32> {
+    for (ship_type, lost) in losses {
+        *ships.entry(ship_type.clone()).or_insert(0) -= lost;
+    }
+    ships.retain(|_, qty| *qty > 0);
+    ships
+}
+
+// Une flotte d'attaque atteint sa cible : toutes les flottes de son groupe (AG) combattent ensemble,
+// aux côtés des flottes alliées stationnées en défense, puis rentrent avec leur part du butin
+async fn resolve_attack_arrival(db: &impl ConnectionTrait, bus: &EventBus, universes: &Universes, f: fleet::Model, now: NaiveDateTime) -> Result<(), DbErr> {
+    let group = match f.acs_group_id {
+        Some(group_id) => fleet::Entity::find()
+            .filter(fleet::Column::AcsGroupId.eq(group_id))
+            .filter(fleet::Column::MissionType.eq("attack"))
+            .filter(fleet::Column::Status.eq("outbound"))
+            .all(db)
+            .await?,
+        None => vec![f.clone()],
+    };
+
+    let defender = match f.target_planet_id {
+        Some(target_id) => locks::lock_planet(db, target_id).await?,
+        None => None,
+    };
+
+    // Cible disparue ou passée en mode vacances : les flottes font demi-tour
+    let Some(def_planet) = defender.filter(|d| !d.is_on_vacation()) else {
+        for member in group {
+            turn_back(db, member, now).await?;
+        }
+        return Ok(());
+    };
+    // Combat aux règles de l'univers de la cible
+    let Some(universe) = &universes.get(db, def_planet.universe_id).await? else {
+        return Err(DbErr::RecordNotFound("Univers introuvable".into()));
+    };
+
+    let attacker_ids: Vec<Uuid> = group.iter().map(|m| m.planet_id).collect();
+    let attackers: HashMap<Uuid, planet::Model> = planet::Entity::find()
+        .filter(planet::Column::Id.is_in(attacker_ids))
+        .order_by_asc(planet::Column::Id)
+        .lock_exclusive()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p))
+        .collect();
+    let name_of = |id: Uuid| attackers.get(&id).map(|p| p.name.clone()).unwrap_or_else(|| "Inconnu".to_string());
+    // Un nom par planète, dans l'ordre du groupe (plusieurs flottes peuvent partir de la même)
+    let mut attacker_names: Vec<String> = Vec::new();
+    for name in group.iter().map(|m| name_of(m.planet_id)) {
+        if !attacker_names.contains(&name) {
+            attacker_names.push(name);
+        }
+    }
+    let attacker_label = attacker_names.join(", ");
+
+    let supporters = find_supporting_fleets(db, def_planet.id, now).await?;
+
+    // Combat : attaquants contre planète + soutiens
+    let att_squadrons: Vec<Squadron> = group.iter().map(|m| parse_ships(&m.ships)).collect();
+    let mut def_squadrons = vec![units::load(db, def_planet.id).await?];
+    def_squadrons.extend(supporters.iter().map(|s| parse_ships(&s.ships)));
+    // Soutes : hyperespace du propriétaire de chaque flotte
+    let mut hyperspace_by_owner: HashMap<Uuid, i32> = HashMap::new();
+    let mut hyperspace_levels = Vec::with_capacity(group.len());
+    for member in &group {
+        let level = match attackers.get(&member.planet_id) {
+            Some(p) => match hyperspace_by_owner.get(&p.owner_id) {
+                Some(level) => *level,
+                None => {
+                    let level = levels::get(&levels::load_research(db, p.owner_id).await?, "hyperspace");
+                    hyperspace_by_owner.insert(p.owner_id, level);
+                    level
+                }
+            },
+            None => 0,
+        };
+        hyperspace_levels.push(level);
+    }
+
+    let outcome = game_logic::resolve_group_pvp(
+        &att_squadrons,
+        &def_squadrons,
+        game_logic::Cost {
+            metal: def_planet.metal_amount,
+            crystal: def_planet.crystal_amount,
+            deuterium: def_planet.deuterium_amount,
+        },
+        universe,
+        &hyperspace_levels,
+    );
+    let result = &outcome.report;
+
+    let target_name = def_planet.name.clone();
+    let mut destroyed_points = apply_defense(db, bus, def_planet, &attacker_label, result, &outcome.defender_losses[0]).await?;
+
+    // Soutiens alliés : pertes, points et rapport
+    for (support, losses) in supporters.into_iter().zip(outcome.defender_losses.iter().skip(1)) {
+        let lost_points = game_logic::get_units_points(losses);
+        destroyed_points += lost_points;
+        if let Some(owner) = locks::lock_planet(db, support.planet_id).await? {
+            let mut owner_active: planet::ActiveModel = owner.into();
+            ranking::add_points(&mut owner_active, "military_lost", lost_points);
+            owner_active.update(db).await?;
+        }
+
+        let report = json!({
+            "winner": result.winner,
+            "log": result.log,
+            "loot": 0,
+            "losses": losses,
+            "is_defense": true,
+            "target_name": target_name,
+        });
+        notification::push(db, bus, support.planet_id, NotificationKind::Defense, &report).await?;
+        messaging::notify(
+            db,
+            bus,
+            support.planet_id,
+            "combat",
+            &format!("Soutien engagé sur {}", target_name),
+            format!("Votre flotte a défendu {} contre {} : {} vaisseaux perdus.", target_name, attacker_label, losses.values().sum::<i32>()),
+            Some(&report),
+        ).await?;
+
+        let survivors = remove_losses(parse_ships(&support.ships), losses);
+        if survivors.is_empty() {
+            support.delete(db).await?;
+        } else {
+            let mut active = support.into_active_model();
+            active.ships = Set(serde_json::to_string(&survivors).unwrap());
+            active.update(db).await?;
+        }
+    }
+
+    // Attaquants : chacun rentre avec sa part du butin
+    let participants: Vec<String> = attacker_names.clone();
+    let mut points: HashMap<Uuid, (f64, f64)> = HashMap::new(); // Planète -> (détruits, perdus)
+    for (i, member) in group.into_iter().enumerate() {
+        let losses = &outcome.attacker_losses[i];
+        let share = outcome.attacker_shares[i];
+        let loot_share = outcome.loot_shares[i];
+
+        let entry = points.entry(member.planet_id).or_insert((0.0, 0.0));
+        entry.0 += destroyed_points * share;
+        entry.1 += game_logic::get_units_points(losses);
+
+        let ships = remove_losses(parse_ships(&member.ships), losses);
+        let report = json!({
+            "winner": result.winner,
+            "log": result.log,
+            "loot": (result.loot.metal + result.loot.crystal) * loot_share,
+            "losses": losses,
+            "ships_lost": losses.values().sum::<i32>(),
+            "result": if result.winner == "attacker" { "victory" } else { "defeat" },
+            "target_name": target_name,
+            "participants": participants,
+        });
+
+        let flight = member.arrival_time.signed_duration_since(member.departure_time).num_seconds();
+        let mut active = member.into_active_model();
+        active.status = Set("returning".to_string());
+        active.return_time = Set(if ships.is_empty() { now } else { now + Duration::seconds(flight) });
+        active.ships = Set(serde_json::to_string(&ships).unwrap());
+        active.cargo_metal = Set(result.loot.metal * loot_share);
+        active.cargo_crystal = Set(result.loot.crystal * loot_share);
+        active.cargo_deuterium = Set(result.loot.deuterium * loot_share);
+        active.report = Set(Some(report.to_string()));
+        active.update(db).await?;
+    }
+
+    // Points cumulés puis écrits une fois par planète d'origine
+    for (planet_id, (destroyed, lost)) in points {
+        if let Some(att) = attackers.get(&planet_id) {
+            let mut att_active: planet::ActiveModel = att.clone().into();
+            ranking::add_points(&mut att_active, "military_destroyed", destroyed);
+            ranking::add_points(&mut att_active, "military_lost", lost);
+            att_active.update(db).await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Fin de mission de soutien : la flotte rentre
+async fn resolve_defend_end(db: &impl ConnectionTrait, f: fleet::Model, now: NaiveDateTime) -> Result<(), DbErr> {
+    turn_back(db, f, now).await
+}
+
+// --- TRANSPORT / STATIONNEMENT ---
+
+// Demi-tour sans rien livrer (cible disparue ou non éligible)
+async fn turn_back(db: &impl ConnectionTrait, f: fleet::Model, now: NaiveDateTime) -> Result<(), DbErr> {
+    let flight = f.arrival_time.signed_duration_since(f.departure_time).num_seconds();
+    let mut active = f.into_active_model();
+    active.status = Set("returning".to_string());
+    active.return_time = Set(now + Duration::seconds(flight));
+    active.update(db).await?;
+    Ok(())
+}
+
+// Un transport livre sa cargaison puis rentre à vide
+async fn resolve_transport_arrival(db: &impl ConnectionTrait, bus: &EventBus, f: fleet::Model, now: NaiveDateTime) -> Result<(), DbErr> {
+    let target = match f.target_planet_id {
+        Some(target_id) => locks::lock_planet(db, target_id).await?,
+        None => None,
+    };
+    let Some(target) = target else {
+        return turn_back(db, f, now).await;
+    };
+
+    let mut target_active: planet::ActiveModel = target.clone().into();
+    target_active.metal_amount = Set(target.metal_amount + f.cargo_metal);
+    target_active.crystal_amount = Set(target.crystal_amount + f.cargo_crystal);
+    target_active.deuterium_amount = Set(target.deuterium_amount + f.cargo_deuterium);
+    target_active.update(db).await?;
+    ledger::record(db, target.id, cargo_delta(&f), "transport", Some(f.id)).await?;
+
+    let delivered = format!("{:.0} métal, {:.0} cristal, {:.0} deutérium", f.cargo_metal, f.cargo_crystal, f.cargo_deuterium);
+    let sender_name = planet::Entity::find_by_id(f.planet_id).one(db).await?
+        .map(|p| p.name)
+        .unwrap_or_else(|| "Inconnu".to_string());
+    messaging::notify(db, bus, f.planet_id, "system", &format!("Livraison effectuée : {}", target.name), format!("Votre transport a livré {}.", delivered), None).await?;
+    if target.id != f.planet_id {
+        messaging::notify(db, bus, target.id, "system", &format!("Livraison reçue de {}", sender_name), format!("Un transport a livré {}.", delivered), None).await?;
+    }
+
+    let flight = f.arrival_time.signed_duration_since(f.departure_time).num_seconds();
+    let mut active = f.into_active_model();
+    active.status = Set("returning".to_string());
+    active.cargo_metal = Set(0.0);
+    active.cargo_crystal = Set(0.0);
+    active.cargo_deuterium = Set(0.0);
+    active.return_time = Set(now + Duration::seconds(flight));
+    active.update(db).await?;
+
+    Ok(())
+}
+
+// Un stationnement transfère définitivement vaisseaux et cargaison sur la planète cible
+async fn resolve_deploy_arrival(db: &impl ConnectionTrait, bus: &EventBus, f: fleet::Model, now: NaiveDateTime) -> Result<(), DbErr> {
+    let origin = locks::lock_planet(db, f.planet_id).await?;
+    let target = match f.target_planet_id {
+        Some(target_id) => locks::lock_planet(db, target_id).await?,
+        None => None,
+    };
+    // La planète cible doit toujours appartenir au même commandant
+    let (Some(origin), Some(target)) = (origin, target) else {
+        return turn_back(db, f, now).await;
+    };
+    if target.owner_id != origin.owner_id {
+        return turn_back(db, f, now).await;
+    }
+
+    let ships = parse_ships(&f.ships);
+    units::add_all(db, target.id, &ships, 1).await?;
+    let mut target_active: planet::ActiveModel = target.clone().into();
+    target_active.metal_amount = Set(target.metal_amount + f.cargo_metal);
+    target_active.crystal_amount = Set(target.crystal_amount + f.cargo_crystal);
+    target_active.deuterium_amount = Set(target.deuterium_amount + f.cargo_deuterium);
+    target_active.dark_matter_amount = Set(target.dark_matter_amount + f.cargo_dark_matter);
+    target_active.update(db).await?;
+    ledger::record(db, target.id, cargo_delta(&f), "deploy", Some(f.id)).await?;
+
+    let body = format!("{} vaisseaux sont désormais stationnés sur {}.", ships.values().sum::<i32>(), target.name);
+    messaging::notify(db, bus, origin.id, "system", &format!("Stationnement effectué : {}", target.name), body, None).await?;
+
+    f.delete(db).await?;
+    Ok(())
+}
+
+// Cargaison d'une flotte, en ressources reçues
+fn cargo_delta(f: &fleet::Model) -> ledger::Delta {
+    ledger::Delta {
+        metal: f.cargo_metal,
+        crystal: f.cargo_crystal,
+        deuterium: f.cargo_deuterium,
+        dark_matter: f.cargo_dark_matter,
+    }
+}
+
+// --- RETOUR ---
+
+// La flotte rentre : vaisseaux et cargaison sont rendus, le rapport est livré
+async fn complete_return(db: &impl ConnectionTrait, bus: &EventBus, f: fleet::Model) -> Result<(), DbErr> {
+    let owner = locks::lock_planet(db, f.planet_id).await?;
+    let notify_owner = owner.as_ref().is_some_and(|p| !p.is_npc);
+    if let Some(p) = owner {
+        let ships = parse_ships(&f.ships);
+
+        units::add_all(db, p.id, &ships, 1).await?;
+        let mut active: planet::ActiveModel = p.clone().into();
+        active.metal_amount = Set(p.metal_amount + f.cargo_metal);
+        active.crystal_amount = Set(p.crystal_amount + f.cargo_crystal);
+        active.deuterium_amount = Set(p.deuterium_amount + f.cargo_deuterium);
+        active.dark_matter_amount = Set(p.dark_matter_amount + f.cargo_dark_matter);
+        active.update(db).await?;
+        ledger::record(db, p.id, cargo_delta(&f), "fleet_return", Some(f.id)).await?;
+    }
+
+    // Rapport de mission (absent si la flotte a fait demi-tour sans combat)
+    if let Some(raw) = f.report.as_deref() {
+        let report: serde_json::Value = serde_json::from_str(raw).unwrap_or_default();
+        let sector = format!("Secteur [{}:{}:{}]", f.target_galaxy, f.target_system, f.target_position);
+        let target_name = report["target_name"].as_str().map(str::to_string).unwrap_or(sector);
+
+        if notify_owner {
+            let (folder, subject) = match f.mission_type.as_str() {
+                "expedition" => ("expedition", format!("Expédition : {}", target_name)),
+                _ => ("combat", format!("Rapport de combat : {}", target_name)),
+            };
+            let body = format!(
+                "Flotte de retour ({}). Cargaison : {:.0} métal, {:.0} cristal, {:.0} deutérium. {} vaisseaux perdus.",
+                report["result"].as_str().unwrap_or("player"),
+                f.cargo_metal,
+                f.cargo_crystal,
+                f.cargo_deuterium,
+                report["ships_lost"].as_i64().unwrap_or(0),
+            );
+            messaging::notify(db, bus, f.planet_id, folder, &subject, body, Some(&report)).await?;
+            notification::push(db, bus, f.planet_id, NotificationKind::for_mission(&f.mission_type), &report).await?;
+        }
+
+        let log = combat_log::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            planet_id: Set(f.planet_id),
+            target_name: Set(target_name),
+            mission_type: Set(f.mission_type.clone()),
+            result: Set(report["result"].as_str().unwrap_or("player").to_string()),
+            loot_metal: Set(f.cargo_metal),
+            loot_crystal: Set(f.cargo_crystal),
+            ships_lost: Set(report["ships_lost"].as_i64().unwrap_or(0) as i32),
+            date: Set(Utc::now().naive_utc()),
+        };
+        log.insert(db).await?;
+    }
+
+    f.delete(db).await?;
+    Ok(())
+}
+
+// --- VUE D'ENSEMBLE ---
+
+#[derive(Serialize)]
+pub struct FleetMovement {
+    id: Uuid,
+    mission_type: String,
+    status: String,
+    is_own: bool,
+    is_hostile: bool,
+    origin_planet_id: Uuid,
+    origin_name: String,
+    origin_coords: (i32, i32, i32),
+    target_planet_id: Option<Uuid>,
+    target_coords: (i32, i32, i32),
+    departure_time: NaiveDateTime,
+    arrival_time: NaiveDateTime,
+    return_time: NaiveDateTime,
+    intel: &'static str,                     // "full", "count" ou "none" pour les flottes étrangères
+    ships: Option<HashMap<String, i32>>,     // Masqué si l'espionnage est insuffisant
+    ship_count: Option<i32>,
+    cargo: Option<game_logic::Cost>,         // Flottes du joueur uniquement
+}
+
+#[derive(Serialize)]
+pub struct FleetOverview {
+    movements: Vec<FleetMovement>, // Flottes du joueur et flottes étrangères encore en vol vers la planète
+    stationed: Vec<FleetMovement>, // Soutiens alliés stationnés sur la planète
+}
+
+// GET /planets/:id/fleets : flottes du joueur, flottes étrangères en approche et soutiens stationnés
+pub async fn get_fleets_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<FleetOverview>, StatusCode> {
+    let me = planet::Entity::find_by_id(id)
+        .one(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let own = fleet::Entity::find()
+        .filter(fleet::Column::PlanetId.eq(id))
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    // Une flotte arrivée n'est plus « en approche » : un soutien reste "outbound" pendant tout son stationnement
+    let now = Utc::now().naive_utc();
+    let incoming = fleet::Entity::find()
+        .filter(fleet::Column::TargetPlanetId.eq(id))
+        .filter(fleet::Column::PlanetId.ne(id))
+        .filter(fleet::Column::Status.eq("outbound"))
+        .filter(fleet::Column::ArrivalTime.gt(now))
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let stationed = supporting_fleets(id, now)
+        .filter(fleet::Column::PlanetId.ne(id))
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let origin_ids: Vec<Uuid> = incoming.iter().chain(&stationed).map(|f| f.planet_id).collect();
+    let origins: HashMap<Uuid, planet::Model> = planet::Entity::find()
+        .filter(planet::Column::Id.is_in(origin_ids))
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|p| (p.id, p))
+        .collect();
+    let research = levels::load_research(&state.db, me.owner_id).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let intel = game_logic::get_fleet_intel(levels::get(&research, "espionage"));
+
+    // Les soutiens stationnés sont entièrement visibles : ils défendent la planète
+    let view = |f: fleet::Model, is_stationed: bool| {
+        let is_own = f.planet_id == id;
+        let ships = parse_ships(&f.ships);
+        let (origin_name, origin_coords) = match (is_own, origins.get(&f.planet_id)) {
+            (true, _) => (me.name.clone(), (me.galaxy, me.system, me.position)),
+            (false, Some(o)) => (o.name.clone(), (o.galaxy, o.system, o.position)),
+            (false, None) => ("Inconnu".to_string(), (0, 0, 0)),
+        };
+        let visibility = if is_own || is_stationed { "full" } else { intel };
+
+        FleetMovement {
+            id: f.id,
+            is_hostile: !is_own && f.mission_type == "attack" && f.status == "outbound" && f.arrival_time > now,
+            mission_type: f.mission_type,
+            status: f.status,
+            is_own,
+            origin_planet_id: f.planet_id,
+            origin_name,
+            origin_coords,
+            target_planet_id: f.target_planet_id,
+            target_coords: (f.target_galaxy, f.target_system, f.target_position),
+            departure_time: f.departure_time,
+            arrival_time: f.arrival_time,
+            return_time: f.return_time,
+            intel: visibility,
+            ship_count: (visibility != "none").then(|| ships.values().sum()),
+            ships: (visibility == "full").then_some(ships),
+            cargo: is_own.then_some(game_logic::Cost { metal: f.cargo_metal, crystal: f.cargo_crystal, deuterium: f.cargo_deuterium }),
+        }
+    };
+
+    let mut movements: Vec<FleetMovement> = own.into_iter().chain(incoming).map(|f| view(f, false)).collect();
+    // Prochain événement en premier (arrivée à l'aller, retour ensuite)
+    movements.sort_by_key(|m| if m.status == "outbound" { m.arrival_time } else { m.return_time });
+    let stationed = stationed.into_iter().map(|f| view(f, true)).collect();
+    Ok(Json(FleetOverview { movements, stationed }))
+}
+
+// --- RAPPEL ---
+
+// POST /fleets/:fleet_id/recall?current_planet_id=...
+// La flotte fait demi-tour et met pour rentrer le temps déjà passé en vol (carburant non remboursé)
+pub async fn recall_fleet_handler(
+    Path(fleet_id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let planet_id = params.get("current_planet_id")
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .unwrap_or_default();
+
+    // Verrou : le rappel et la résolution de la mission ne peuvent pas se croiser
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let f = match locks::lock_fleet(&txn, fleet_id).await {
+        Ok(Some(f)) if f.planet_id == planet_id => f,
+        Ok(_) => return (StatusCode::NOT_FOUND, Json(json!({"error": "Flotte introuvable"}))).into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+
+    // Une mission résolue (ou sur le point de l'être) ne peut plus être annulée
+    let now = Utc::now().naive_utc();
+    if f.status != "outbound" || now >= f.hold_end {
+        return (StatusCode::CONFLICT, Json(json!({"error": "Mission déjà résolue, rappel impossible"}))).into_response();
+    }
+
+    // Pendant le stationnement, le retour dure tout le trajet aller
+    let elapsed = now.min(f.arrival_time).signed_duration_since(f.departure_time).num_seconds();
+    let return_time = now + Duration::seconds(std::cmp::max(1, elapsed));
+
+    let mut active = f.into_active_model();
+    active.status = Set("returning".to_string());
+    active.hold_end = Set(now);
+    active.return_time = Set(return_time);
+    active.report = Set(None);
+
+    let updated = match active.update(&txn).await {
+        Ok(updated) => updated,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    match txn.commit().await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "status": "success", "fleet": updated }))).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    }
+}
+
+// --- ENVOI ---
+
+#[derive(Deserialize)]
+pub struct SendFleetPayload {
+    pub mission: String, // "transport", "deploy", "attack" ou "defend"
+    pub target_planet_id: Uuid,
+    pub ships: HashMap<String, i32>,
+    #[serde(default)]
+    pub cargo: game_logic::Cost,
+    #[serde(default)]
+    pub acs_group_id: Option<Uuid>, // Attaque : id de la flotte meneuse à rejoindre
+    #[serde(default)]
+    pub hold_hours: i32, // Soutien : durée de stationnement (heures de jeu)
+}
+
+const SEND_MISSIONS: [&str; 4] = ["transport", "deploy", "attack", "defend"];
+
+// POST /planets/:id/send-fleet : transport vers n'importe quelle planète, stationnement vers une planète du joueur,
+// attaque (seule ou groupée avec des alliés) et soutien d'une planète alliée
+pub async fn send_fleet_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(payload): Json<SendFleetPayload>,
+) -> impl IntoResponse {
+    if !SEND_MISSIONS.contains(&payload.mission.as_str()) {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Mission inconnue"}))).into_response();
+    }
+    if payload.target_planet_id == id {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": "La cible doit être une autre planète"}))).into_response();
+    }
+
+    // La planète d'origine reste verrouillée jusqu'au commit : stock et hangar ne partent qu'une fois
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let p = match locks::lock_planet(&txn, id).await {
+        Ok(Some(p)) => p,
+        _ => return (StatusCode::NOT_FOUND, Json(json!({"error": "Planet not found"}))).into_response(),
+    };
+    let target = match planet::Entity::find_by_id(payload.target_planet_id).one(&txn).await {
+        Ok(Some(t)) => t,
+        _ => return (StatusCode::NOT_FOUND, Json(json!({"error": "Cible introuvable"}))).into_response(),
+    };
+    if target.universe_id != p.universe_id {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Cible dans un autre univers"}))).into_response();
+    }
+    let universe = match state.universes.require(&txn, p.universe_id).await {
+        Ok(u) => u,
+        Err(code) => return (code, Json(json!({"error": "Univers introuvable"}))).into_response(),
+    };
+
+    let own_target = target.owner_id == p.owner_id;
+    if p.is_on_vacation() {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Mode vacances actif : aucun ordre de flotte possible"}))).into_response();
+    }
+    if payload.mission == "deploy" && !own_target {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Stationnement possible uniquement sur vos planètes"}))).into_response();
+    }
+    if (target.is_npc && payload.mission != "attack") || (!own_target && target.is_on_vacation()) {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Cible non éligible"}))).into_response();
+    }
+    let combat_mission = payload.mission == "attack" || payload.mission == "defend";
+    if payload.mission == "attack" {
+        if own_target || alliance::same_alliance(&txn, p.id, target.id).await.unwrap_or(false) {
+            return (StatusCode::FORBIDDEN, Json(json!({"error": "Impossible d'attaquer un membre de votre alliance"}))).into_response();
+        }
+        if !target.is_npc {
+            match state.protection.check(game_logic::get_score(&p), game_logic::get_score(&target)) {
+                Some("newbie") => return (StatusCode::FORBIDDEN, Json(json!({"error": "Cible sous protection débutant"}))).into_response(),
+                Some(_) => return (StatusCode::FORBIDDEN, Json(json!({"error": "Cible hors de portée : écart de score trop important"}))).into_response(),
+                None => {}
+            }
+        }
+    }
+    if payload.mission == "defend" {
+        if own_target || !alliance::same_alliance(&txn, p.id, target.id).await.unwrap_or(false) {
+            return (StatusCode::FORBIDDEN, Json(json!({"error": "Soutien possible uniquement chez un allié"}))).into_response();
+        }
+        if !(1..=8).contains(&payload.hold_hours) {
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": "Durée de soutien invalide (1 à 8 heures)"}))).into_response();
+        }
+    }
+
+    // 1. Composition et soute
+    let ships: HashMap<String, i32> = payload.ships.into_iter().filter(|(_, qty)| *qty > 0).collect();
+    if ships.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Aucune flotte envoyée"}))).into_response();
+    }
+    let inventory = match units::load(&txn, id).await {
+        Ok(inventory) => inventory,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    for (ship_type, qty) in &ships {
+        if !SHIP_TYPES.contains(&ship_type.as_str()) || *qty > units::count(&inventory, ship_type) {
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": "Flotte insuffisante"}))).into_response();
+        }
+    }
+
+    let cargo = payload.cargo;
+    if combat_mission && cargo.metal + cargo.crystal + cargo.deuterium > 0.0 {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Les flottes de combat partent soute vide"}))).into_response();
+    }
+    if cargo.metal < 0.0 || cargo.crystal < 0.0 || cargo.deuterium < 0.0 {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Cargaison invalide"}))).into_response();
+    }
+    let lv = match levels::load(&txn, &p).await {
+        Ok(lv) => lv,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let capacity: f64 = ships.iter()
+        .map(|(ship_type, qty)| game_logic::get_cargo_capacity(ship_type, *qty, levels::get(&lv, "hyperspace")))
+        .sum();
+    if cargo.metal + cargo.crystal + cargo.deuterium > capacity {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": format!("Soute insuffisante ({:.0} max)", capacity)}))).into_response();
+    }
+
+    // 2. Stock à jour avant chargement
+    let metal = game_logic::calculate_resources(&universe, game_logic::ResourceType::Metal, levels::get(&lv, "metal"), p.temperature_max, p.metal_amount, p.last_update);
+    let crystal = game_logic::calculate_resources(&universe, game_logic::ResourceType::Crystal, levels::get(&lv, "crystal"), p.temperature_max, p.crystal_amount, p.last_update);
+    let deuterium = game_logic::calculate_resources(&universe, game_logic::ResourceType::Deuterium, levels::get(&lv, "deuterium"), p.temperature_max, p.deuterium_amount, p.last_update);
+    if cargo.metal > metal || cargo.crystal > crystal || cargo.deuterium > deuterium {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Ressources insuffisantes"}))).into_response();
+    }
+    // Carburant pris sur le stock, en plus de la cargaison
+    let distance = game_logic::get_distance((p.galaxy, p.system, p.position), (target.galaxy, target.system, target.position));
+    let fuel = game_logic::get_fuel_consumption(&ships, distance);
+    if cargo.deuterium + fuel > deuterium {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": format!("Deutérium insuffisant pour le vol ({:.0} requis)", fuel)}))).into_response();
+    }
+
+    // 3. Horaires
+    let now = Utc::now().naive_utc();
+    let flight = game_logic::get_flight_time(&universe, distance, slowest_speed(&ships));
+    let mut arrival = now + Duration::seconds(flight);
+    let mut hold_end = arrival;
+    let mut acs_group_id = None;
+    let fleet_id = Uuid::new_v4();
+
+    if payload.mission == "attack" {
+        match payload.acs_group_id {
+            // Rejoindre une AG : la flotte doit pouvoir arriver avant la meneuse, puis s'aligne sur son horaire
+            Some(leader_id) => {
+                let leader = match locks::lock_fleet(&txn, leader_id).await {
+                    Ok(Some(l)) if l.acs_group_id == Some(leader_id) && l.status == "outbound" && l.target_planet_id == Some(target.id) && l.arrival_time > now => l,
+                    Ok(_) => return (StatusCode::NOT_FOUND, Json(json!({"error": "Attaque groupée introuvable ou déjà arrivée"}))).into_response(),
+                    Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+                };
+                if leader.planet_id != p.id && !alliance::same_alliance(&txn, p.id, leader.planet_id).await.unwrap_or(false) {
+                    return (StatusCode::FORBIDDEN, Json(json!({"error": "Attaque groupée réservée aux membres de l'alliance"}))).into_response();
+                }
+                if arrival > leader.arrival_time {
+                    return (StatusCode::BAD_REQUEST, Json(json!({"error": "Flotte trop lente pour rejoindre l'attaque groupée"}))).into_response();
+                }
+                arrival = leader.arrival_time;
+                hold_end = arrival;
+                acs_group_id = Some(leader_id);
+            }
+            // Nouvelle attaque : la flotte devient meneuse de son propre groupe
+            None => acs_group_id = Some(fleet_id),
+        }
+    }
+    if payload.mission == "defend" {
+        let hold = universe.fleet_hold(payload.hold_hours);
+        hold_end = arrival + Duration::seconds(hold);
+    }
+    let return_time = if payload.mission == "deploy" { arrival } else { hold_end + Duration::seconds(flight) };
+
+    let movement = fleet::ActiveModel {
+        id: Set(fleet_id),
+        planet_id: Set(id),
+        mission_type: Set(payload.mission.clone()),
+        status: Set("outbound".to_string()),
+        target_planet_id: Set(Some(target.id)),
+        target_galaxy: Set(target.galaxy),
+        target_system: Set(target.system),
+        target_position: Set(target.position),
+        acs_group_id: Set(acs_group_id),
+        ships: Set(serde_json::to_string(&ships).unwrap()),
+        cargo_metal: Set(cargo.metal),
+        cargo_crystal: Set(cargo.crystal),
+        cargo_deuterium: Set(cargo.deuterium),
+        departure_time: Set(now),
+        arrival_time: Set(arrival),
+        hold_hours: Set(if payload.mission == "defend" { payload.hold_hours } else { 0 }),
+        hold_end: Set(hold_end),
+        return_time: Set(return_time),
+        ..Default::default()
+    };
+
+    let mut active: planet::ActiveModel = p.clone().into();
+    active.metal_amount = Set(metal - cargo.metal);
+    active.crystal_amount = Set(crystal - cargo.crystal);
+    active.deuterium_amount = Set(deuterium - cargo.deuterium - fuel);
+    active.last_update = Set(now);
+
+    let movement = match movement.insert(&txn).await {
+        Ok(m) => m,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response(),
+    };
+    let production = ledger::record(&txn, id, ledger::Delta::since(&p, metal, crystal, deuterium), "production", None).await;
+    let departure = ledger::record(&txn, id, ledger::Delta::spend(&cargo), "fleet_departure", Some(movement.id)).await;
+    let burned = ledger::record(&txn, id, ledger::Delta { deuterium: -fuel, ..Default::default() }, "fuel", Some(movement.id)).await;
+    let boarded = units::add_all(&txn, id, &ships, -1).await;
+    if production.is_err() || departure.is_err() || burned.is_err() || boarded.is_err() {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response();
+    }
+    let updated_planet = match active.update(&txn).await {
+        Ok(updated) => updated,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response(),
+    };
+    if txn.commit().await.is_err() {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response();
+    }
+
+    if !own_target {
+        state.events.publish(target.id, GameEvent::IncomingFleet {
+            fleet_id: movement.id,
+            origin_name: p.name.clone(),
+            mission_type: movement.mission_type.clone(),
+            arrival_time: arrival,
+        });
+    }
+
+    (StatusCode::OK, Json(json!({ "status": "success", "planet": updated_planet, "fleet": movement }))).into_response()
+}
@@ -1,9 +1,13 @@
-use serde::Serialize;
-use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
 
-pub const SPEED_FACTOR: f64 = 500000.0; // Vitesse du jeu
+use crate::entities::{planet, universe};
+use crate::levels::{self, Levels};
 
-#[derive(Serialize, Clone)]
+pub const VACATION_MIN_HOURS: i64 = 48; // Durée minimale du mode vacances (temps réel)
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Cost {
     pub metal: f64,
     pub crystal: f64,
@@ -18,12 +22,8 @@ pub struct PvpReport {
     pub debris: Cost,
     pub attacker_losses: i32,
     pub defender_losses: i32,
-}
-
-pub struct CombatResult {
-    pub victory: bool,
-    pub message: String,
-    pub ships_lost: i32,
+    pub att_lost: Squadron, // Unités détruites par type
+    pub def_lost: Squadron,
 }
 
 // --- CALCULS RESSOURCES ---
@@ -34,7 +34,7 @@ pub enum ResourceType {
     Deuterium,
 }
 
-pub fn calculate_resources(res_type: ResourceType, level: i32, current_amount: f64, last_update: chrono::NaiveDateTime) -> f64 {
+pub fn calculate_resources(universe: &Universe, res_type: ResourceType, level: i32, temperature_max: i32, current_amount: f64, last_update: chrono::NaiveDateTime) -> f64 {
     let now = chrono::Utc::now().naive_utc();
     let duration = now.signed_duration_since(last_update).num_seconds() as f64;
     
@@ -42,15 +42,49 @@ pub fn calculate_resources(res_type: ResourceType, level: i32, current_amount: f
     let base_production = match res_type {
         ResourceType::Metal => 30.0 * (level as f64) * 1.1f64.powi(level),
         ResourceType::Crystal => 20.0 * (level as f64) * 1.1f64.powi(level),
-        ResourceType::Deuterium => 10.0 * (level as f64) * 1.1f64.powi(level),
+        ResourceType::Deuterium => 10.0 * (level as f64) * 1.1f64.powi(level) * deuterium_temperature_factor(temperature_max),
     };
 
-    // Production par seconde * Speed Factor
-    let production_per_sec = (base_production / 3600.0) * SPEED_FACTOR;
+    // Production par seconde * vitesse économique de l'univers
+    let production_per_sec = (base_production / 3600.0) * universe.economy_speed;
     
     current_amount + (production_per_sec * duration)
 }
 
+// Le deutérium se condense mieux sur les planètes froides (x1 à 110°C)
+pub fn deuterium_temperature_factor(temperature_max: i32) -> f64 {
+    (1.44 - 0.004 * temperature_max as f64).max(0.1)
+}
+
+// --- PLANÈTES ---
+
+// Cases constructibles possibles selon la position : petites planètes près de l'étoile
+// et en bordure, grandes au milieu du système
+pub fn get_field_range(position: i32) -> (i32, i32) {
+    match position {
+        1..=3 => (40, 80),
+        4..=6 => (90, 170),
+        7..=9 => (140, 200),
+        10..=12 => (110, 180),
+        _ => (60, 150),
+    }
+}
+
+// Température maximale de base (°C) : 240 en position 1, environ -140 en position 15
+pub fn get_base_temperature(position: i32) -> i32 {
+    240 - (position - 1) * 27
+}
+
+// Diamètre (km) d'une planète de `fields` cases
+pub fn get_diameter(fields: i32) -> i32 {
+    ((fields as f64).sqrt() * 1000.0).round() as i32
+}
+
+// Cases occupées : une par niveau de bâtiment (les recherches n'en occupent pas)
+pub fn get_fields_used(levels: &Levels) -> i32 {
+    levels::BUILDING_TYPES.iter().map(|b| levels::get(levels, b)).sum()
+}
+
 // --- COÛTS ---
 
 pub fn get_upgrade_cost(building_type: &str, level: i32) -> Cost {
@@ -82,181 +116,640 @@ pub fn get_upgrade_cost(building_type: &str, level: i32) -> Cost {
             crystal: 400.0 * factor,
             deuterium: 200.0 * factor,
         },
-        "laser" => Cost {
-            metal: 1500.0 * factor,
-            crystal: 500.0 * factor,
-            deuterium: 100.0 * factor,
-        },
         "espionage" => Cost { 
             metal: 200.0 * factor, 
             crystal: 1000.0 * factor, 
             deuterium: 200.0 * factor
         },
+        "hyperspace" => Cost {
+            metal: 0.0,
+            crystal: 4000.0 * factor,
+            deuterium: 2000.0 * factor,
+        },
+        "astrophysics" => Cost {
+            metal: 4000.0 * 1.75f64.powi(level - 1),
+            crystal: 8000.0 * 1.75f64.powi(level - 1),
+            deuterium: 4000.0 * 1.75f64.powi(level - 1),
+        },
         _ => Cost { metal: 0.0, crystal: 0.0, deuterium: 0.0 },
     }
 }
 
 // --- TEMPS DE CONSTRUCTION ---
 
-// Pour les bâtiments (basé sur le coût)
-pub fn get_build_time(metal_cost: f64, crystal_cost: f64) -> i64 {
-    let total_cost = metal_cost + crystal_cost;
-    let hours = (total_cost / 2500.0) / SPEED_FACTOR; 
+// Pour les bâtiments et recherches (basé sur le coût, vitesse propre aux recherches)
+pub fn get_build_time(universe: &Universe, building_type: &str, cost: &Cost) -> i64 {
+    let total_cost = cost.metal + cost.crystal;
+    let speed = if get_upgrade_category(building_type) == "research" { universe.research_speed } else { universe.economy_speed };
+    let hours = (total_cost / 2500.0) / speed;
     let seconds = (hours * 3600.0) as i64;
     std::cmp::max(2, seconds)
 }
 
 // Pour la flotte (basé sur la quantité et vitesse de base) - C'EST CELLE QUI MANQUAIT
-pub fn get_ship_production_time(qty: i32) -> i64 {
-    let base_time_per_unit = 20.0 / SPEED_FACTOR; 
+pub fn get_ship_production_time(universe: &Universe, qty: i32) -> i64 {
+    let base_time_per_unit = 20.0 / universe.economy_speed;
     std::cmp::max(1, (base_time_per_unit * qty as f64) as i64)
 }
 
-// --- STATS FLOTTE & DÉFENSES ---
+// --- CATALOGUE DES DÉFENSES ---
+
+#[derive(Serialize, Clone)]
+pub struct DefenseStats {
+    pub metal: f64,
+    pub crystal: f64,
+    pub unique: bool, // Un seul exemplaire par planète (dômes)
+    pub combat: CombatStats,
+}
+
+fn defense(metal: f64, crystal: f64, attack: f64, shield: f64, unique: bool) -> DefenseStats {
+    DefenseStats { metal, crystal, unique, combat: CombatStats { attack, shield, hull: (metal + crystal) / 10.0 } }
+}
 
-pub fn get_light_hunter_stats() -> (f64, f64) {
-    (3000.0, 1000.0) // Metal, Crystal
+pub fn get_defense_stats(defense_type: &str) -> Option<DefenseStats> {
+    match defense_type {
+        "missile_launcher" => Some(defense(2000.0, 0.0, 80.0, 20.0, false)),
+        "light_laser" => Some(defense(1500.0, 500.0, 100.0, 25.0, false)),
+        "heavy_laser" => Some(defense(6000.0, 2000.0, 250.0, 100.0, false)),
+        "ion_cannon" => Some(defense(2000.0, 6000.0, 150.0, 500.0, false)),   // Gros bouclier, faible attaque
+        "gauss_cannon" => Some(defense(20000.0, 15000.0, 1100.0, 200.0, false)),
+        "plasma_turret" => Some(defense(50000.0, 50000.0, 3000.0, 300.0, false)),
+        "small_shield_dome" => Some(defense(10000.0, 10000.0, 0.0, 2000.0, true)),
+        "large_shield_dome" => Some(defense(50000.0, 50000.0, 0.0, 10000.0, true)),
+        _ => None,
+    }
 }
 
-pub fn get_spy_probe_stats() -> (f64, f64) {
-    (0.0, 1000.0)
+// --- CATALOGUE DES VAISSEAUX ---
+
+#[derive(Serialize, Clone)]
+pub struct ShipStats {
+    pub metal: f64,
+    pub crystal: f64,
+    pub cargo: f64, // Capacité de soute de base
+    pub speed: i32, // Vitesse de base
+    pub fuel: f64,  // Consommation de deutérium de base
+    pub combat: CombatStats,
 }
 
-pub fn get_missile_launcher_stats() -> (f64, f64) {
-    (2000.0, 0.0)
+fn ship(metal: f64, crystal: f64, cargo: f64, speed: i32, fuel: f64, attack: f64, shield: f64) -> ShipStats {
+    // Coque = 10% du coût, comme pour les défenses
+    ShipStats { metal, crystal, cargo, speed, fuel, combat: CombatStats { attack, shield, hull: (metal + crystal) / 10.0 } }
 }
 
-pub fn get_plasma_turret_stats() -> (f64, f64) {
-    (50000.0, 50000.0)
+pub fn get_ship_stats(ship_type: &str) -> Option<ShipStats> {
+    match ship_type {
+        "light_hunter" => Some(ship(3000.0, 1000.0, 50.0, 12500, 20.0, 50.0, 10.0)),
+        "heavy_hunter" => Some(ship(6000.0, 4000.0, 100.0, 10000, 75.0, 150.0, 25.0)),
+        "cruiser" => Some(ship(20000.0, 7000.0, 800.0, 15000, 300.0, 400.0, 50.0)),
+        "battleship" => Some(ship(45000.0, 15000.0, 1500.0, 10000, 500.0, 1000.0, 200.0)),
+        "bomber" => Some(ship(50000.0, 25000.0, 500.0, 4000, 700.0, 1000.0, 500.0)),     // Spécialiste des défenses
+        "destroyer" => Some(ship(60000.0, 50000.0, 2000.0, 5000, 1000.0, 2000.0, 500.0)),
+        "recycler" => Some(ship(10000.0, 6000.0, 20000.0, 2000, 300.0, 1.0, 10.0)),
+        "spy_probe" => Some(ship(0.0, 1000.0, 0.0, 100000000, 1.0, 0.0, 0.0)),
+        "small_cargo" => Some(ship(2000.0, 2000.0, 5000.0, 5000, 10.0, 5.0, 10.0)),
+        "large_cargo" => Some(ship(6000.0, 6000.0, 25000.0, 7500, 50.0, 5.0, 25.0)),
+        _ => None,
+    }
 }
 
-// --- COMBAT & LOGIQUE ---
+// Caractéristiques de combat d'un vaisseau ou d'une défense
+#[derive(Serialize, Clone, Copy)]
+pub struct CombatStats {
+    pub attack: f64, // Dégâts par tir
+    pub shield: f64, // Absorbé à chaque tour, régénéré au suivant
+    pub hull: f64,   // Points de structure
+}
+
+pub fn get_combat_stats(unit_type: &str) -> Option<CombatStats> {
+    get_ship_stats(unit_type).map(|s| s.combat).or_else(|| get_defense_stats(unit_type).map(|d| d.combat))
+}
+
+// Tir rapide : nombre moyen de tirs qu'une unité enchaîne contre ce type de cible (1 = aucun)
+pub fn get_rapid_fire(shooter: &str, target: &str) -> i32 {
+    match (shooter, target) {
+        ("spy_probe", _) => 1,
+        (s, "spy_probe") if get_ship_stats(s).is_some() => 5,
+        ("cruiser", "light_hunter") => 6,
+        ("cruiser", "missile_launcher") => 10,
+        ("heavy_hunter", "small_cargo") => 3,
+        ("battleship", "heavy_hunter") => 3,
+        ("bomber", "missile_launcher" | "light_laser") => 20,
+        ("bomber", "heavy_laser" | "ion_cannon") => 10,
+        ("bomber", "gauss_cannon" | "plasma_turret") => 5,
+        ("destroyer", "light_laser") => 10,
+        ("destroyer", "battleship") => 2,
+        _ => 1,
+    }
+}
 
-pub fn simulate_combat(fleet_size: i32, defense_bonus: i32) -> CombatResult {
-    let mut rng = rand::thread_rng();
-    let pirates_strength = rng.gen_range(10..100);
-    let player_strength = fleet_size + (defense_bonus * 5);
-
-    if player_strength > pirates_strength {
-        let loss_percent = rng.gen_range(0.0..0.2);
-        let ships_lost = (fleet_size as f64 * loss_percent) as i32;
-        
-        CombatResult {
-            victory: true,
-            message: format!("Victoire ! L'ennemi (Force {}) a été écrasé.", pirates_strength),
-            ships_lost,
+// Coût unitaire (métal, cristal) d'un vaisseau ou d'une défense, None si le type est inconnu
+pub fn get_unit_cost(unit_type: &str) -> Option<(f64, f64)> {
+    get_ship_stats(unit_type)
+        .map(|s| (s.metal, s.crystal))
+        .or_else(|| get_defense_stats(unit_type).map(|d| (d.metal, d.crystal)))
+}
+
+// Deutérium brûlé par une flotte sur une distance donnée
+pub fn get_fuel_consumption(ships: &HashMap<String, i32>, distance: f64) -> f64 {
+    let base: f64 = ships
+        .iter()
+        .filter_map(|(ship_type, qty)| get_ship_stats(ship_type).map(|s| s.fuel * *qty as f64))
+        .sum();
+    (1.0 + base * distance / 35000.0).round()
+}
+
+// Soute totale d'un groupe de vaisseaux, +5% par niveau de technologie hyperespace
+pub fn get_cargo_capacity(ship_type: &str, count: i32, hyperspace_level: i32) -> f64 {
+    let base = get_ship_stats(ship_type).map(|s| s.cargo).unwrap_or(0.0);
+    base * count as f64 * (1.0 + 0.05 * hyperspace_level as f64)
+}
+
+// Expéditions simultanées autorisées : 1 + racine du niveau d'astrophysique
+pub fn get_max_expeditions(astrophysics_level: i32) -> i32 {
+    1 + (astrophysics_level.max(0) as f64).sqrt().floor() as i32
+}
+
+// --- DÉTECTION DES FLOTTES ---
+
+// Ce que l'espionnage révèle d'une flotte étrangère en approche : "none", "count" ou "full"
+pub fn get_fleet_intel(espionage_level: i32) -> &'static str {
+    match espionage_level {
+        l if l >= 6 => "full",  // Composition détaillée
+        l if l >= 3 => "count", // Nombre total de vaisseaux
+        _ => "none",
+    }
+}
+
+// --- SCORE ---
+
+pub const SCORE_CATEGORIES: [&str; 7] = [
+    "total", "economy", "research", "military", "military_built", "military_destroyed", "military_lost",
+];
+
+// Points gagnés pour une dépense (1 point = 1000 ressources)
+pub fn get_cost_points(cost: &Cost) -> f64 {
+    (cost.metal + cost.crystal + cost.deuterium) / 1000.0
+}
+
+// Catégorie de points d'une amélioration
+pub fn get_upgrade_category(building_type: &str) -> &'static str {
+    match building_type {
+        "metal" | "crystal" | "deuterium" | "research" => "economy",
+        _ => "research",
+    }
+}
+
+// Valeur (en ressources) d'une unité de flotte ou de défense
+pub fn get_unit_value(unit_type: &str) -> f64 {
+    get_unit_cost(unit_type).map(|(metal, crystal)| metal + crystal).unwrap_or(0.0)
+}
+
+// Valeur en points d'un ensemble d'unités (1 point = 1000 ressources)
+pub fn get_units_points(units: &Squadron) -> f64 {
+    units.iter().map(|(unit_type, qty)| *qty as f64 * get_unit_value(unit_type)).sum::<f64>() / 1000.0
+}
+
+pub fn get_category_score(p: &planet::Model, category: &str) -> f64 {
+    let military = (p.points_military_built - p.points_military_lost).max(0.0);
+    match category {
+        "economy" => p.points_economy,
+        "research" => p.points_research,
+        "military" => military,
+        "military_built" => p.points_military_built,
+        "military_destroyed" => p.points_military_destroyed,
+        "military_lost" => p.points_military_lost,
+        _ => p.points_economy + p.points_research + military,
+    }
+}
+
+pub fn get_score(p: &planet::Model) -> i32 {
+    get_category_score(p, "total") as i32
+}
+
+// --- UNIVERS ---
+
+pub const POSITIONS_PER_SYSTEM: i32 = 15; // Emplacements par système solaire
+
+// Réglages d'un univers (voir la table universe), chargés au démarrage
+#[derive(Clone, Copy, Serialize)]
+pub struct Universe {
+    pub id: Uuid,
+    pub economy_speed: f64,        // Production, bâtiments et chantier spatial
+    pub research_speed: f64,       // Durée des recherches
+    pub fleet_speed: f64,          // Durée des vols
+    pub expedition_speed: f64,     // Durée du stationnement en expédition
+    pub galaxies: i32,
+    pub systems_per_galaxy: i32,
+    pub debris_ratio: f64,         // Part du coût des vaisseaux détruits laissée en débris
+    pub defense_debris_ratio: f64, // Idem pour les défenses détruites (0 = aucune)
+}
+
+impl From<&universe::Model> for Universe {
+    fn from(u: &universe::Model) -> Self {
+        Universe {
+            id: u.id,
+            economy_speed: u.economy_speed,
+            research_speed: u.research_speed,
+            fleet_speed: u.fleet_speed,
+            expedition_speed: u.expedition_speed,
+            galaxies: u.galaxies,
+            systems_per_galaxy: u.systems_per_galaxy,
+            debris_ratio: u.debris_ratio,
+            defense_debris_ratio: u.defense_debris_ratio,
         }
+    }
+}
+
+impl Universe {
+    // Stationnement d'une expédition (heures de jeu -> secondes réelles)
+    pub fn expedition_hold(&self, hours: i32) -> i64 {
+        std::cmp::max(1, (hours as f64 * 3600.0 / self.expedition_speed) as i64)
+    }
+
+    // Stationnement d'une flotte de soutien, au rythme des vols
+    pub fn fleet_hold(&self, hours: i32) -> i64 {
+        std::cmp::max(1, (hours as f64 * 3600.0 / self.fleet_speed) as i64)
+    }
+
+    pub fn contains(&self, galaxy: i32, system: i32) -> bool {
+        (1..=self.galaxies).contains(&galaxy) && (1..=self.systems_per_galaxy).contains(&system)
+    }
+}
+
+// --- PROTECTION DES JOUEURS ---
+
+#[derive(Clone, Copy)]
+pub struct ProtectionRules {
+    pub newbie_score: i32, // En dessous, le joueur ne peut pas être attaqué
+    pub range_ratio: f64,  // Écart de score max entre attaquant et cible
+}
+
+impl ProtectionRules {
+    pub fn from_env() -> Self {
+        let newbie_score = std::env::var("NEWBIE_PROTECTION_SCORE").ok().and_then(|v| v.parse().ok()).unwrap_or(100);
+        let range_ratio = std::env::var("ATTACK_RANGE_RATIO").ok().and_then(|v| v.parse().ok()).unwrap_or(5.0);
+        ProtectionRules { newbie_score, range_ratio }
+    }
+
+    pub fn is_newbie(&self, score: i32) -> bool {
+        score < self.newbie_score
+    }
+
+    // None si l'attaque est permise, sinon "newbie" ou "out_of_range"
+    pub fn check(&self, attacker_score: i32, target_score: i32) -> Option<&'static str> {
+        if self.is_newbie(target_score) {
+            return Some("newbie");
+        }
+        let (low, high) = (attacker_score.min(target_score).max(1) as f64, attacker_score.max(target_score) as f64);
+        if high / low > self.range_ratio {
+            return Some("out_of_range");
+        }
+        None
+    }
+}
+
+// --- DÉPLACEMENTS ---
+
+// Distance entre deux coordonnées (formule OGame)
+pub fn get_distance(from: (i32, i32, i32), to: (i32, i32, i32)) -> f64 {
+    if from.0 != to.0 {
+        20000.0 * (from.0 - to.0).abs() as f64
+    } else if from.1 != to.1 {
+        2700.0 + 95.0 * (from.1 - to.1).abs() as f64
     } else {
-        let loss_percent = rng.gen_range(0.3..0.8);
-        let ships_lost = (fleet_size as f64 * loss_percent) as i32;
-        
-        CombatResult {
-            victory: false,
-            message: format!("Défaite... Les pirates (Force {}) étaient trop nombreux.", pirates_strength),
-            ships_lost,
+        1000.0 + 5.0 * (from.2 - to.2).abs() as f64
+    }
+}
+
+// Temps de vol en secondes pour une flotte à la vitesse du vaisseau le plus lent
+pub fn get_flight_time(universe: &Universe, distance: f64, slowest_speed: i32) -> i64 {
+    let seconds = 10.0 + 3500.0 * (10.0 * distance / slowest_speed as f64).sqrt();
+    std::cmp::max(1, (seconds / universe.fleet_speed) as i64)
+}
+
+// --- COMBAT & LOGIQUE ---
+
+const COMBAT_ROUNDS: usize = 6;
+
+// Effectifs d'un camp au fil de la bataille (fractions possibles : pertes moyennes)
+type Forces = HashMap<String, f64>;
+
+// Un tour de tir d'un camp : unités détruites chez l'adversaire.
+// Chaque tir vise une unité au hasard (d'où la répartition au prorata des effectifs) ;
+// le tir rapide permet d'enchaîner plusieurs tirs, un tir inférieur à 1% du bouclier ricoche,
+// et le bouclier de chaque unité absorbe les dégâts avant la coque.
+fn volley(shooters: &[(&str, CombatStats, f64)], targets: &Forces) -> Forces {
+    let total: f64 = targets.values().sum();
+    let mut damage: Forces = HashMap::new();
+    if total <= 0.0 {
+        return damage;
+    }
+
+    for (name, stats, count) in shooters {
+        if *count <= 0.0 || stats.attack <= 0.0 {
+            continue;
         }
+        // Probabilité de pouvoir tirer à nouveau après un tir
+        let chain: f64 = targets
+            .iter()
+            .map(|(t, c)| c / total * (1.0 - 1.0 / get_rapid_fire(name, t) as f64))
+            .sum();
+        let shots = count / (1.0 - chain);
+        for (t, c) in targets {
+            let Some(target) = get_combat_stats(t) else { continue };
+            if stats.attack < target.shield * 0.01 {
+                continue;
+            }
+            *damage.entry(t.clone()).or_insert(0.0) += shots * (c / total) * stats.attack;
+        }
+    }
+
+    damage
+        .into_iter()
+        .filter_map(|(t, dmg)| {
+            let stats = get_combat_stats(&t)?;
+            let count = targets.get(&t).copied().unwrap_or(0.0);
+            let to_hull = (dmg - count * stats.shield).max(0.0);
+            Some((t, (to_hull / stats.hull.max(1.0)).min(count)))
+        })
+        .collect()
+}
+
+fn apply_kills(forces: &mut Forces, kills: &Forces) {
+    for (t, killed) in kills {
+        if let Some(count) = forces.get_mut(t) {
+            *count -= killed;
+            if *count < 0.5 {
+                *count = 0.0; // Moins d'une demi-unité : détruite
+            }
+        }
+    }
+}
+
+fn shooters(forces: &Forces) -> Vec<(&str, CombatStats, f64)> {
+    forces
+        .iter()
+        .filter_map(|(t, c)| get_combat_stats(t).map(|stats| (t.as_str(), stats, *c)))
+        .collect()
+}
+
+fn to_forces(units: &Squadron) -> Forces {
+    units
+        .iter()
+        .filter(|(t, qty)| **qty > 0 && get_combat_stats(t).is_some())
+        .map(|(t, qty)| (t.clone(), *qty as f64))
+        .collect()
+}
+
+fn losses(initial: &Forces, remaining: &Forces) -> Squadron {
+    initial
+        .iter()
+        .map(|(t, c)| (t.clone(), (c - remaining.get(t).copied().unwrap_or(0.0)).round() as i32))
+        .filter(|(_, lost)| *lost > 0)
+        .collect()
+}
+
+fn alive(forces: &Forces) -> bool {
+    forces.values().any(|c| *c > 0.0)
+}
+
+// Issue brute d'une bataille, sans butin ni débris
+pub struct Battle {
+    pub log: Vec<String>,
+    pub att_lost: Squadron,
+    pub def_lost: Squadron,
+    pub attacker_alive: bool,
+    pub defender_alive: bool,
+}
+
+// Jusqu'à 6 tours de tirs simultanés entre deux camps
+pub fn fight(attacker: &Squadron, defender: &Squadron) -> Battle {
+    let mut log = Vec::new();
+    let (att_start, def_start) = (to_forces(attacker), to_forces(defender));
+    let (mut att, mut def) = (att_start.clone(), def_start.clone());
+
+    for round in 1..=COMBAT_ROUNDS {
+        if !alive(&att) || !alive(&def) {
+            break;
+        }
+        let (def_kills, att_kills) = (volley(&shooters(&att), &def), volley(&shooters(&def), &att));
+        apply_kills(&mut def, &def_kills);
+        apply_kills(&mut att, &att_kills);
+        log.push(format!(
+            "TOUR {} : {:.0} unité(s) détruite(s) en face, {:.0} perdue(s).",
+            round,
+            def_kills.values().sum::<f64>(),
+            att_kills.values().sum::<f64>()
+        ));
+    }
+
+    Battle {
+        log,
+        att_lost: losses(&att_start, &att),
+        def_lost: losses(&def_start, &def),
+        attacker_alive: alive(&att),
+        defender_alive: alive(&def),
     }
 }
 
+// Bataille entre une flotte d'attaque et une planète (vaisseaux et défenses à quai).
+// L'attaquant l'emporte s'il ne reste rien en face.
 pub fn resolve_pvp(
-    att_hunters: i32, 
-    att_cruisers: i32,
-    def_hunters: i32, 
-    def_cruisers: i32, 
-    def_lasers: i32,      
-    def_missiles: i32,    
-    def_plasmas: i32,     
-    def_resources: Cost   
+    attacker: &Squadron,
+    defender: &Squadron,
+    def_resources: Cost,
+    universe: &Universe,
 ) -> PvpReport {
-    let mut log = Vec::new();
-    
-    // ATTAQUANT
-    let att_power = (att_hunters * 10) + (att_cruisers * 50);
-    
-    // DÉFENSEUR
-    let def_power = (def_hunters * 10) 
-                  + (def_cruisers * 50) 
-                  + (def_lasers * 20)      
-                  + (def_missiles * 20) 
-                  + (def_plasmas * 200);
-
-    log.push(format!("Analyse : Force Attaque {} vs Force Défense {}", att_power, def_power));
-
-    let winner;
-    let loot;
-    let attacker_losses;
-    let defender_losses;
-    let defense_struct_lost; 
-
-    // PERTES
-    let att_lost_hunters;
-    let att_lost_cruisers;
-    let def_lost_hunters;
-    let def_lost_cruisers;
-
-   if att_power > def_power {
-        winner = "attacker".to_string();
+    let mut log = vec![format!(
+        "Analyse : Force Attaque {:.0} vs Force Défense {:.0}",
+        get_firepower(attacker),
+        get_firepower(defender)
+    )];
+    let Battle { log: rounds, att_lost, def_lost, attacker_alive, defender_alive } = fight(attacker, defender);
+    log.extend(rounds);
+
+    let winner = if attacker_alive && !defender_alive { "attacker" } else { "defender" };
+    let loot = if winner == "attacker" {
         log.push("VICTOIRE : Défenses percées.".to_string());
-        
-        let ratio = if att_power > 0 { def_power as f64 / att_power as f64 } else { 0.0 };
-        let loss_percent = 0.1 + (0.4 * ratio);
-        
-        att_lost_hunters = (att_hunters as f64 * loss_percent) as i32;
-        att_lost_cruisers = (att_cruisers as f64 * loss_percent) as i32;
-        
-        attacker_losses = att_lost_hunters + att_lost_cruisers; // Total unités perdues
-
-        def_lost_hunters = (def_hunters as f64 * 0.8) as i32;
-        def_lost_cruisers = (def_cruisers as f64 * 0.8) as i32;
-        
-        defender_losses = def_lost_hunters + def_lost_cruisers;
-        defense_struct_lost = (def_missiles as f64 * 0.6) as i32; // On ne compte pas les plasmas ici pour simplifier
-
-        loot = Cost {
+        Cost {
             metal: def_resources.metal * 0.5,
             crystal: def_resources.crystal * 0.5,
             deuterium: def_resources.deuterium * 0.5,
-        };
-
+        }
     } else {
-        winner = "defender".to_string();
+        if attacker_alive {
+            log.push("REPLI : Le combat s'éternise, la flotte d'attaque se retire.".to_string());
+        }
         log.push("DÉFAITE : La forteresse a tenu bon.".to_string());
-        
-        att_lost_hunters = (att_hunters as f64 * 0.8) as i32;
-        att_lost_cruisers = (att_cruisers as f64 * 0.8) as i32;
-        attacker_losses = att_lost_hunters + att_lost_cruisers;
-
-        def_lost_hunters = (def_hunters as f64 * 0.1) as i32;
-        def_lost_cruisers = (def_cruisers as f64 * 0.1) as i32;
-        defender_losses = def_lost_hunters + def_lost_cruisers;
-        
-        defense_struct_lost = (def_missiles as f64 * 0.1) as i32;
-        
-        loot = Cost { metal: 0.0, crystal: 0.0, deuterium: 0.0 };
-    }
+        Cost::default()
+    };
 
     // CALCUL DU CDR (CHAMP DE DÉBRIS)
-    // 30% du coût des vaisseaux détruits (Métal et Cristal uniquement)
-    let (hunter_m, hunter_c) = get_light_hunter_stats();
-    let (cruiser_m, cruiser_c) = (20000.0, 7000.0); // Stats croiseur hardcodées ici pour l'instant
+    // Part du coût des unités détruites fixée par l'univers, vaisseaux et défenses séparément (Métal et Cristal uniquement)
+    let mut debris = Cost::default();
+    for (unit_type, qty) in att_lost.iter().chain(def_lost.iter()) {
+        let ratio = if get_ship_stats(unit_type).is_some() { universe.debris_ratio } else { universe.defense_debris_ratio };
+        let (metal, crystal) = get_unit_cost(unit_type).unwrap_or((0.0, 0.0));
+        debris.metal += *qty as f64 * metal * ratio;
+        debris.crystal += *qty as f64 * crystal * ratio;
+    }
+    if debris.metal > 0.0 || debris.crystal > 0.0 {
+        log.push(format!("DÉBRIS : Un champ de débris s'est formé ({:.0} M, {:.0} C).", debris.metal, debris.crystal));
+    }
 
-    let debris_metal = ((att_lost_hunters + def_lost_hunters) as f64 * hunter_m * 0.3) 
-                     + ((att_lost_cruisers + def_lost_cruisers) as f64 * cruiser_m * 0.3);
-    
-    let debris_crystal = ((att_lost_hunters + def_lost_hunters) as f64 * hunter_c * 0.3) 
-                       + ((att_lost_cruisers + def_lost_cruisers) as f64 * cruiser_c * 0.3);
+    PvpReport {
+        winner: winner.to_string(),
+        log,
+        loot,
+        debris,
+        attacker_losses: att_lost.values().sum(),
+        defender_losses: def_lost.values().sum(),
+        att_lost,
+        def_lost,
+    }
+}
+
+// --- COMBAT DE GROUPE (AG) ---
+
+// Unités engagées par un participant (type -> nombre)
+pub type Squadron = HashMap<String, i32>;
+
+// Puissance de feu brute (somme des attaques)
+pub fn get_firepower(units: &Squadron) -> f64 {
+    units
+        .iter()
+        .filter_map(|(t, qty)| get_combat_stats(t).map(|s| s.attack * *qty as f64))
+        .sum()
+}
+
+pub struct GroupPvpReport {
+    pub report: PvpReport,             // Combat global (totaux, butin, débris)
+    pub attacker_losses: Vec<Squadron>,
+    pub defender_losses: Vec<Squadron>, // Index 0 = planète attaquée, puis les flottes alliées
+    pub attacker_shares: Vec<f64>,     // Part de chaque attaquant dans les destructions (puissance de feu)
+    pub loot_shares: Vec<f64>,         // Part de chaque attaquant dans le butin (soute des survivants)
+}
 
-    if debris_metal > 0.0 || debris_crystal > 0.0 {
-        log.push(format!("DÉBRIS : Un champ de débris s'est formé ({:.0} M, {:.0} C).", debris_metal, debris_crystal));
+fn merge(side: &[Squadron]) -> Squadron {
+    let mut total = Squadron::new();
+    for squadron in side {
+        for (t, qty) in squadron {
+            *total.entry(t.clone()).or_insert(0) += qty;
+        }
     }
+    total
+}
 
+// Pertes réparties au prorata des unités engagées par chacun, type par type.
+// Méthode du plus fort reste : les parts entières d'abord, puis les vaisseaux restants aux plus
+// grandes fractions, pour que la somme des parts retombe exactement sur les pertes du combat
+fn split_losses(squadrons: &[Squadron], lost: &Squadron) -> Vec<Squadron> {
+    let total = merge(squadrons);
+    let mut shares: Vec<Squadron> = vec![Squadron::new(); squadrons.len()];
+    for (t, lost) in lost {
+        let engaged = total.get(t).copied().unwrap_or(0);
+        if engaged <= 0 || *lost <= 0 {
+            continue;
+        }
+        let lost = (*lost).min(engaged);
+        let quotas: Vec<f64> = squadrons
+            .iter()
+            .map(|s| s.get(t).copied().unwrap_or(0) as f64 * lost as f64 / engaged as f64)
+            .collect();
+        let mut counts: Vec<i32> = quotas.iter().map(|q| q.floor() as i32).collect();
+
+        let mut by_remainder: Vec<usize> = (0..squadrons.len()).collect();
+        by_remainder.sort_by(|&a, &b| (quotas[b] - quotas[b].floor()).total_cmp(&(quotas[a] - quotas[a].floor())).then(a.cmp(&b)));
+        let left = lost - counts.iter().sum::<i32>();
+        for &i in by_remainder.iter().take(left as usize) {
+            counts[i] += 1;
+        }
 
- PvpReport {
-        winner,
-        log,
-        loot,
-        debris: Cost { metal: debris_metal, crystal: debris_crystal, deuterium: 0.0 }, // <-- On renvoie le CDR
+        for (share, count) in shares.iter_mut().zip(counts) {
+            if count > 0 {
+                share.insert(t.clone(), count);
+            }
+        }
+    }
+    shares
+}
+
+// hyperspace_levels : niveau d'hyperespace du propriétaire de chaque flotte d'attaque (soutes)
+pub fn resolve_group_pvp(
+    attackers: &[Squadron],
+    defenders: &[Squadron],
+    def_resources: Cost,
+    universe: &Universe,
+    hyperspace_levels: &[i32],
+) -> GroupPvpReport {
+    let mut report = resolve_pvp(&merge(attackers), &merge(defenders), def_resources, universe);
+    if attackers.len() > 1 || defenders.len() > 1 {
+        report.log.insert(0, format!("AG : {} flotte(s) d'attaque contre {} défenseur(s).", attackers.len(), defenders.len()));
+    }
+    let attacker_losses = split_losses(attackers, &report.att_lost);
+
+    // Le butin ne dépasse pas ce que les survivants peuvent emporter, et se répartit selon leur soute
+    let capacities: Vec<f64> = attackers
+        .iter()
+        .zip(&attacker_losses)
+        .zip(hyperspace_levels)
+        .map(|((ships, losses), level)| {
+            ships.iter()
+                .map(|(t, qty)| get_cargo_capacity(t, qty - losses.get(t).copied().unwrap_or(0), *level))
+                .sum()
+        })
+        .collect();
+    let total_capacity: f64 = capacities.iter().sum();
+    let wanted = report.loot.metal + report.loot.crystal + report.loot.deuterium;
+    if wanted > total_capacity {
+        let ratio = if wanted > 0.0 { total_capacity / wanted } else { 0.0 };
+        report.loot = Cost {
+            metal: report.loot.metal * ratio,
+            crystal: report.loot.crystal * ratio,
+            deuterium: report.loot.deuterium * ratio,
+        };
+        report.log.push(format!("BUTIN : Soutes pleines, {:.0} ressources emportées.", total_capacity));
+    }
+
+    let att_power = get_firepower(&merge(attackers)).max(1.0);
+    GroupPvpReport {
         attacker_losses,
-        defender_losses: defender_losses + defense_struct_lost
+        defender_losses: split_losses(defenders, &report.def_lost),
+        attacker_shares: attackers.iter().map(|s| get_firepower(s) / att_power).collect(),
+        loot_shares: capacities.iter().map(|c| if total_capacity > 0.0 { c / total_capacity } else { 0.0 }).collect(),
+        report,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn squadron(ships: &[(&str, i32)]) -> Squadron {
+        ships.iter().map(|(t, n)| (t.to_string(), *n)).collect()
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn split_losses_sums_to_combat_losses() {
+        // Trois flottes égales, 2 pertes : l'arrondi séparé donnait 1 + 1 + 1
+        let squadrons = vec![
+            squadron(&[("light_hunter", 10)]),
+            squadron(&[("light_hunter", 10)]),
+            squadron(&[("light_hunter", 10), ("cruiser", 3)]),
+        ];
+        let lost = squadron(&[("light_hunter", 2), ("cruiser", 3)]);
+
+        let shares = split_losses(&squadrons, &lost);
+        assert_eq!(merge(&shares), lost);
+        assert_eq!(shares[0], squadron(&[("light_hunter", 1)]));
+        assert_eq!(shares[1], squadron(&[("light_hunter", 1)]));
+        assert_eq!(shares[2], squadron(&[("cruiser", 3)]));
+
+        // Chaque part reste dans la limite des vaisseaux engagés
+        let squadrons = vec![squadron(&[("light_hunter", 1)]), squadron(&[("light_hunter", 6)])];
+        let shares = split_losses(&squadrons, &squadron(&[("light_hunter", 5)]));
+        assert_eq!(merge(&shares), squadron(&[("light_hunter", 5)]));
+        assert!(shares.iter().zip(&squadrons).all(|(share, s)| share.iter().all(|(t, n)| *n <= s[t])));
+    }
+}
@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::HeaderName, StatusCode},
+    response::{IntoResponse, Json},
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{planet, resource_ledger};
+use crate::game_logic::Cost;
+use crate::AppState;
+
+// Écart minimal entre deux soldes de production déclenchés par une simple consultation :
+// entre-temps le stock est calculé à l'affichage sans être écrit (ni journalisé)
+pub const PRODUCTION_SETTLE_SECONDS: i64 = 600;
+
+// Motifs possibles d'un mouvement de ressources
+pub const REASONS: [&str; 11] = [
+    "production",      // Production des mines, soldée au plus toutes les PRODUCTION_SETTLE_SECONDS en consultation
+    "construction",    // Coût d'un bâtiment ou d'une recherche
+    "shipyard",        // Coût d'une commande au chantier spatial
+    "plunder",         // Butin d'une attaque instantanée (ancienne route /attack, historique seulement)
+    "plundered",       // Ressources pillées par un attaquant
+    "recycling",       // Récolte d'un champ de débris
+    "fleet_departure", // Cargaison chargée au départ d'une flotte
+    "fuel",            // Deutérium consommé par le vol d'une flotte
+    "fleet_return",    // Cargaison déchargée au retour (butin, expédition...)
+    "transport",       // Livraison reçue d'un transport
+    "deploy",          // Cargaison d'une flotte stationnée
+];
+
+// Variation signée des ressources d'une planète
+#[derive(Clone, Copy, Default)]
+pub struct Delta {
+    pub metal: f64,
+    pub crystal: f64,
+    pub deuterium: f64,
+    pub dark_matter: f64,
+}
+
+impl Delta {
+    pub fn gain(cost: &Cost) -> Self {
+        Delta { metal: cost.metal, crystal: cost.crystal, deuterium: cost.deuterium, dark_matter: 0.0 }
+    }
+
+    pub fn spend(cost: &Cost) -> Self {
+        Delta { metal: -cost.metal, crystal: -cost.crystal, deuterium: -cost.deuterium, dark_matter: 0.0 }
+    }
+
+    // Écart entre le stock enregistré et le nouveau stock (production soldée)
+    pub fn since(p: &planet::Model, metal: f64, crystal: f64, deuterium: f64) -> Self {
+        Delta {
+            metal: metal - p.metal_amount,
+            crystal: crystal - p.crystal_amount,
+            deuterium: deuterium - p.deuterium_amount,
+            dark_matter: 0.0,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        [self.metal, self.crystal, self.deuterium, self.dark_matter].iter().all(|v| v.abs() < 1e-9)
+    }
+}
+
+// Ajoute une ligne au journal, à appeler dans la même transaction que la modification du stock
+pub async fn record(db: &impl ConnectionTrait, planet_id: Uuid, delta: Delta, reason: &str, related_id: Option<Uuid>) -> Result<(), DbErr> {
+    if delta.is_zero() {
+        return Ok(());
+    }
+
+    resource_ledger::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        planet_id: Set(planet_id),
+        metal: Set(delta.metal),
+        crystal: Set(delta.crystal),
+        deuterium: Set(delta.deuterium),
+        dark_matter: Set(delta.dark_matter),
+        reason: Set(reason.to_string()),
+        related_id: Set(related_id),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+// --- HANDLERS ---
+
+// GET /planets/:id/ledger?reason=plunder&page=1&per_page=50
+pub async fn get_ledger_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let page = params.get("page").and_then(|s| s.parse::<u64>().ok()).unwrap_or(1).max(1);
+    let per_page = params.get("per_page").and_then(|s| s.parse::<u64>().ok()).unwrap_or(50).clamp(1, 200);
+
+    let mut query = resource_ledger::Entity::find().filter(resource_ledger::Column::PlanetId.eq(id));
+    if let Some(reason) = params.get("reason") {
+        if !REASONS.contains(&reason.as_str()) {
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": "Motif inconnu"}))).into_response();
+        }
+        query = query.filter(resource_ledger::Column::Reason.eq(reason.as_str()));
+    }
+
+    let total = query.clone().count(&state.db).await.unwrap_or(0);
+    let entries = query
+        .order_by_desc(resource_ledger::Column::CreatedAt)
+        .offset((page - 1) * per_page)
+        .limit(per_page)
+        .all(&state.db)
+        .await
+        .unwrap_or_default();
+
+    (
+        [(HeaderName::from_static("x-total-count"), total.to_string())],
+        Json(entries),
+    ).into_response()
+}
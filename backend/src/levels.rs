@@ -0,0 +1,98 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{planet, planet_building, player_research};
+
+// Bâtiments : propres à chaque planète, une case par niveau
+pub const BUILDING_TYPES: [&str; 4] = ["metal", "crystal", "deuterium", "research"];
+// Recherches : communes à toutes les planètes d'un joueur (owner_id)
+pub const RESEARCH_TYPES: [&str; 4] = ["energy_tech", "espionage", "hyperspace", "astrophysics"];
+
+// Niveaux vus depuis une planète : ses bâtiments et les recherches de son propriétaire (type -> niveau)
+pub type Levels = HashMap<String, i32>;
+
+pub fn is_known(level_type: &str) -> bool {
+    BUILDING_TYPES.contains(&level_type) || is_research(level_type)
+}
+
+pub fn is_research(level_type: &str) -> bool {
+    RESEARCH_TYPES.contains(&level_type)
+}
+
+pub fn get(levels: &Levels, level_type: &str) -> i32 {
+    levels.get(level_type).copied().unwrap_or(0)
+}
+
+pub async fn load(db: &impl ConnectionTrait, planet: &planet::Model) -> Result<Levels, DbErr> {
+    let mut levels: Levels = planet_building::Entity::find()
+        .filter(planet_building::Column::PlanetId.eq(planet.id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|b| (b.building_type, b.level))
+        .collect();
+    levels.extend(load_research(db, planet.owner_id).await?);
+    Ok(levels)
+}
+
+pub async fn load_research(db: &impl ConnectionTrait, owner_id: Uuid) -> Result<Levels, DbErr> {
+    Ok(player_research::Entity::find()
+        .filter(player_research::Column::OwnerId.eq(owner_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|r| (r.research_type, r.level))
+        .collect())
+}
+
+pub async fn set_building(db: &impl ConnectionTrait, planet_id: Uuid, building_type: &str, level: i32) -> Result<(), DbErr> {
+    let row = planet_building::ActiveModel {
+        planet_id: Set(planet_id),
+        building_type: Set(building_type.to_string()),
+        level: Set(level),
+    };
+    planet_building::Entity::insert(row)
+        .on_conflict(
+            OnConflict::columns([planet_building::Column::PlanetId, planet_building::Column::BuildingType])
+                .update_column(planet_building::Column::Level)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn set_research(db: &impl ConnectionTrait, owner_id: Uuid, research_type: &str, level: i32) -> Result<(), DbErr> {
+    let row = player_research::ActiveModel {
+        owner_id: Set(owner_id),
+        research_type: Set(research_type.to_string()),
+        level: Set(level),
+    };
+    player_research::Entity::insert(row)
+        .on_conflict(
+            OnConflict::columns([player_research::Column::OwnerId, player_research::Column::ResearchType])
+                .update_column(player_research::Column::Level)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+// Fin de construction ou de recherche : niveau suivant.
+// Lecture puis écriture : la planète doit être verrouillée par l'appelant.
+pub async fn raise(db: &impl ConnectionTrait, planet: &planet::Model, level_type: &str) -> Result<(), DbErr> {
+    if is_research(level_type) {
+        let current = get(&load_research(db, planet.owner_id).await?, level_type);
+        set_research(db, planet.owner_id, level_type, current + 1).await
+    } else {
+        let current = planet_building::Entity::find_by_id((planet.id, level_type.to_string()))
+            .one(db)
+            .await?
+            .map(|b| b.level)
+            .unwrap_or(0);
+        set_building(db, planet.id, level_type, current + 1).await
+    }
+}
@@ -0,0 +1,50 @@
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use uuid::Uuid;
+
+use crate::entities::{fleet, planet};
+
+// Verrous de ligne (SELECT ... FOR UPDATE), à prendre dans une transaction : deux requêtes
+// concurrentes sur la même planète s'exécutent l'une après l'autre au lieu de dépenser
+// deux fois le même stock. Le verrou est relâché au commit ou au rollback.
+// Ordre global, pour éviter l'interblocage : les planètes (par id) avant les flottes.
+
+pub async fn lock_planet(db: &impl ConnectionTrait, id: Uuid) -> Result<Option<planet::Model>, DbErr> {
+    planet::Entity::find_by_id(id).lock_exclusive().one(db).await
+}
+
+// Deux planètes verrouillées toujours dans le même ordre, pour éviter l'interblocage
+// entre A attaque B et B attaque A
+pub async fn lock_planet_pair(db: &impl ConnectionTrait, a: Uuid, b: Uuid) -> Result<(Option<planet::Model>, Option<planet::Model>), DbErr> {
+    if a <= b {
+        let first = lock_planet(db, a).await?;
+        Ok((first, lock_planet(db, b).await?))
+    } else {
+        let second = lock_planet(db, b).await?;
+        Ok((lock_planet(db, a).await?, second))
+    }
+}
+
+// Toutes les planètes d'un joueur (état commun au compte, comme la recherche),
+// dans l'ordre des id comme lock_planet_pair
+pub async fn lock_owner_planets(db: &impl ConnectionTrait, owner_id: Uuid) -> Result<Vec<planet::Model>, DbErr> {
+    planet::Entity::find()
+        .filter(planet::Column::OwnerId.eq(owner_id))
+        .order_by_asc(planet::Column::Id)
+        .lock_exclusive()
+        .all(db)
+        .await
+}
+
+// Plusieurs planètes quelconques (flotte et sa cible, attaque groupée...), dans l'ordre des id
+pub async fn lock_planets(db: &impl ConnectionTrait, ids: &[Uuid]) -> Result<Vec<planet::Model>, DbErr> {
+    planet::Entity::find()
+        .filter(planet::Column::Id.is_in(ids.to_vec()))
+        .order_by_asc(planet::Column::Id)
+        .lock_exclusive()
+        .all(db)
+        .await
+}
+
+pub async fn lock_fleet(db: &impl ConnectionTrait, id: Uuid) -> Result<Option<fleet::Model>, DbErr> {
+    fleet::Entity::find_by_id(id).lock_exclusive().one(db).await
+}
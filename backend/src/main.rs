@@ -1,51 +1,64 @@
 use axum::{
     extract::{Path, State, Query},
-    http::{Method, StatusCode},
+    http::{header::HeaderName, Method, StatusCode},
     response::{IntoResponse, Json},
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use sea_orm::{
     ActiveModelTrait, Database, DatabaseConnection,
     EntityTrait, Set, IntoActiveModel, // <--- C'EST L'IMPORT QUI MANQUAIT !
-    QueryFilter, QueryOrder, ColumnTrait, QuerySelect, 
+    QueryFilter, QueryOrder, ColumnTrait, QuerySelect, PaginatorTrait, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, to_string};
 use std::net::SocketAddr;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 use uuid::Uuid;
 use chrono::{Utc, Duration};
-use rand::Rng;
 
+mod alliance;
 mod auth;
 mod game_logic;
+mod ledger;
+mod levels;
+mod locks;
+mod messaging;
 mod combat;
 mod entities; 
+mod events;
+mod expedition;
+mod fleet;
+mod notification;
+mod npc;
+mod placement;
+mod ranking;
+mod units;
+mod universe;
 
 use entities::planet;
 use entities::combat_log;
+use entities::fleet as fleet_entity;
 
 #[derive(Clone)]
 struct AppState {
     db: DatabaseConnection,
+    universes: universe::Universes,
+    protection: game_logic::ProtectionRules,
+    events: events::EventBus,
+    jwt_secret: Arc<str>, // Clé de signature des tokens
 }
 
 #[derive(Serialize)]
-struct RankItem {
-    rank: usize,
-    planet_name: String,
-    score: i32,
-    is_me: bool,
-    id: Uuid,
-}
-
-#[derive(Deserialize)]
-struct AttackPayload {
-    target_planet_id: Uuid,
-    hunters: i32,
-    cruisers: i32,
+struct PlanetView {
+    #[serde(flatten)]
+    planet: planet::Model,
+    units: units::Inventory, // Vaisseaux et défenses à quai
+    levels: levels::Levels, // Bâtiments de la planète et recherches du joueur
+    unread_notifications: u64,
+    fields_used: i32,
 }
 
 #[tokio::main]
@@ -53,26 +66,61 @@ async fn main() {
     dotenvy::dotenv().ok();
     
     let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let jwt_secret: Arc<str> = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set").into();
     let db = Database::connect(&db_url).await.unwrap();
 
-    let state = AppState { db };
+    // Repaires pirates : placement, reconstruction et raids en tâche de fond
+    let universes = universe::Universes::load(&db).await.expect("Impossible de charger les univers");
+    let protection = game_logic::ProtectionRules::from_env();
+    let events = events::EventBus::new();
+    npc::spawn_npc_loop(db.clone(), universes.clone(), protection, events.clone());
+    ranking::spawn_snapshot_loop(db.clone());
+
+    let state = AppState { db, universes, protection, events, jwt_secret };
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST])
-        .allow_headers(Any);
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers(Any)
+        .expose_headers([HeaderName::from_static("x-total-count")]); // Totaux de pagination
 
     let app = Router::new()
         .route("/register", post(auth::register_handler))
         .route("/config", get(get_game_config_handler))
+        .route("/universes", get(universe::list_universes_handler))
+        .route("/ws", get(events::ws_handler))
         .route("/login", post(auth::login_handler))
         .route("/planets/:id", get(get_planet_handler))
         .route("/planets/:id/upgrade/:type", post(upgrade_mine_handler))
         .route("/planets/:id/build-fleet/:type/:qty", post(build_fleet_handler))
         .route("/planets/:id/expedition", post(expedition_handler))
-        .route("/planets/:id/clear-report", post(clear_report_handler))
-        .route("/ranking", get(get_ranking_handler))
-        .route("/attack", post(attack_handler))
+        .route("/planets/:id/fleets", get(fleet::get_fleets_handler))
+        .route("/fleets/:fleet_id/recall", post(fleet::recall_fleet_handler))
+        .route("/planets/:id/send-fleet", post(fleet::send_fleet_handler))
+        .route("/planets/:id/notifications", get(notification::list_notifications_handler))
+        .route("/planets/:id/notifications/read-all", post(notification::mark_all_read_handler))
+        .route("/planets/:id/notifications/:notification_id/read", post(notification::mark_read_handler))
+        .route("/planets/:id/vacation", post(vacation_handler))
+        .route("/planets/:id/ledger", get(ledger::get_ledger_handler))
+        .route("/ranking", get(ranking::get_ranking_handler))
+        .route("/ranking/history/:planet_id", get(ranking::get_ranking_history_handler))
+        .route("/ranking/alliances", get(alliance::get_alliance_ranking_handler))
+        .route("/messages", get(messaging::list_messages_handler).post(messaging::send_message_handler))
+        .route("/messages/unread", get(messaging::unread_counts_handler))
+        .route("/messages/read-all", post(messaging::mark_all_read_handler))
+        .route("/messages/:id", delete(messaging::delete_message_handler))
+        .route("/messages/:id/read", post(messaging::mark_read_handler))
+        .route("/alliances", post(alliance::create_alliance_handler))
+        .route("/alliances/:id", get(alliance::get_alliance_handler))
+        .route("/alliances/:id/disband", post(alliance::disband_alliance_handler))
+        .route("/alliances/:id/apply", post(alliance::apply_handler))
+        .route("/alliances/:id/applications", get(alliance::list_applications_handler))
+        .route("/alliances/:id/applications/:application_id/:decision", post(alliance::decide_application_handler))
+        .route("/alliances/:id/leave", post(alliance::leave_alliance_handler))
+        .route("/alliances/:id/circular", post(alliance::circular_handler))
+        .route("/alliances/:id/kick/:planet_id", post(alliance::kick_member_handler))
+        .route("/alliances/:id/ranks", post(alliance::create_rank_handler))
+        .route("/alliances/:id/members/:planet_id/rank", post(alliance::assign_rank_handler))
         .route("/planets/:id/reports", get(get_reports_handler))
         .route("/spy", post(spy_handler))
         .route("/recycle", post(recycle_handler))
@@ -89,54 +137,31 @@ async fn main() {
 
 // --- HANDLERS ---
 
-async fn get_game_config_handler() -> impl IntoResponse {
-    Json(json!({
-        "speed_factor": game_logic::SPEED_FACTOR
-    }))
-}
-
-async fn get_ranking_handler(
+// GET /config?universe_id=... (ou ?current_planet_id=...)
+async fn get_game_config_handler(
     State(state): State<AppState>,
     Query(params): Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
-    
-    let current_planet_id = params.get("current_planet_id")
-        .and_then(|s| Uuid::parse_str(s).ok())
-        .unwrap_or_default();
-
-    let planets = planet::Entity::find().all(&state.db).await.unwrap_or_default();
-
-    let mut ranked_planets: Vec<RankItem> = planets.into_iter().map(|p| {
-        let score = (p.metal_mine_level + p.crystal_mine_level + p.deuterium_mine_level 
-                     + p.energy_tech_level + p.research_lab_level + p.laser_battery_level) * 100
-                     + (p.light_hunter_count + p.cruiser_count + p.recycler_count) * 10
-                     + (p.missile_launcher_count + p.plasma_turret_count) * 20;
-        
-        RankItem {
-            rank: 0, 
-            planet_name: p.name,
-            score,
-            is_me: p.id == current_planet_id,
-            id: p.id,
-        }
-    }).collect();
-
-    ranked_planets.sort_by(|a, b| b.score.cmp(&a.score));
-
-    for (i, item) in ranked_planets.iter_mut().enumerate() {
-        item.rank = i + 1;
-    }
-
-    Json(ranked_planets)
+    let universe = match state.universes.resolve(&state.db, &params).await {
+        Ok(u) => u,
+        Err(code) => return (code, Json(json!({"error": "Univers introuvable"}))).into_response(),
+    };
+    Json(json!({
+        "speed_factor": universe.economy_speed, // Production affichée par le client
+        "universe": universe
+    })).into_response()
 }
 
 async fn get_planet_handler(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> Result<Json<planet::Model>, StatusCode> {
+) -> Result<Json<PlanetView>, StatusCode> {
     
-    let p_opt = planet::Entity::find_by_id(id)
-        .one(&state.db)
+    // Flottes arrivées ou rentrées depuis la dernière visite
+    fleet::process_fleets(&state.db, &state.events, &state.universes).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let txn = state.db.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let p_opt = locks::lock_planet(&txn, id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -146,30 +171,37 @@ async fn get_planet_handler(
     let elapsed = now.signed_duration_since(p.last_update).num_seconds();
 
     let mut active: planet::ActiveModel = p.clone().into();
+    let lv = levels::load(&txn, &p).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let mut unsettled = None; // Stock affiché mais pas encore soldé en base
 
-    if elapsed > 0 {
-        let new_metal = game_logic::calculate_resources(game_logic::ResourceType::Metal, p.metal_mine_level, p.metal_amount, p.last_update);
-        let new_crystal = game_logic::calculate_resources(game_logic::ResourceType::Crystal, p.crystal_mine_level, p.crystal_amount, p.last_update);
-        let new_deut = game_logic::calculate_resources(game_logic::ResourceType::Deuterium, p.deuterium_mine_level, p.deuterium_amount, p.last_update);
-
-        active.metal_amount = Set(new_metal);
-        active.crystal_amount = Set(new_crystal);
-        active.deuterium_amount = Set(new_deut);
+    if elapsed > 0 && p.is_on_vacation() {
+        // Production gelée : on avance simplement l'horloge
         active.last_update = Set(now);
+    } else if elapsed > 0 {
+        let universe = state.universes.require(&txn, p.universe_id).await?;
+        let new_metal = game_logic::calculate_resources(&universe, game_logic::ResourceType::Metal, levels::get(&lv, "metal"), p.temperature_max, p.metal_amount, p.last_update);
+        let new_crystal = game_logic::calculate_resources(&universe, game_logic::ResourceType::Crystal, levels::get(&lv, "crystal"), p.temperature_max, p.crystal_amount, p.last_update);
+        let new_deut = game_logic::calculate_resources(&universe, game_logic::ResourceType::Deuterium, levels::get(&lv, "deuterium"), p.temperature_max, p.deuterium_amount, p.last_update);
+
+        // Consultation fréquente : le solde attend, last_update reste en place et rien n'est perdu
+        if elapsed < ledger::PRODUCTION_SETTLE_SECONDS {
+            unsettled = Some((new_metal, new_crystal, new_deut));
+        } else {
+            active.metal_amount = Set(new_metal);
+            active.crystal_amount = Set(new_crystal);
+            active.deuterium_amount = Set(new_deut);
+            active.last_update = Set(now);
+            ledger::record(&txn, id, ledger::Delta::since(&p, new_metal, new_crystal, new_deut), "production", None)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        }
     }
 
     if let Some(end_date) = p.construction_end {
         if now >= end_date {
             let type_str = p.construction_type.clone().unwrap_or_default();
-            match type_str.as_str() {
-                "metal" => active.metal_mine_level = Set(p.metal_mine_level + 1),
-                "crystal" => active.crystal_mine_level = Set(p.crystal_mine_level + 1),
-                "deuterium" => active.deuterium_mine_level = Set(p.deuterium_mine_level + 1),
-                "energy_tech" => active.energy_tech_level = Set(p.energy_tech_level + 1),
-                "research" => active.research_lab_level = Set(p.research_lab_level + 1),
-                "laser" => active.laser_battery_level = Set(p.laser_battery_level + 1),
-                "espionage" => active.espionage_tech_level = Set(p.espionage_tech_level + 1),
-                _ => {}
+            if levels::is_known(&type_str) {
+                levels::raise(&txn, &p, &type_str).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
             }
             active.construction_end = Set(None);
             active.construction_type = Set(None);
@@ -180,14 +212,8 @@ async fn get_planet_handler(
         if now >= fleet_end {
             let fleet_str = p.pending_fleet_type.clone().unwrap_or_default();
             let qty = p.pending_fleet_count;
-            match fleet_str.as_str() {
-                "light_hunter" => active.light_hunter_count = Set(p.light_hunter_count + qty),
-                "cruiser" => active.cruiser_count = Set(p.cruiser_count + qty),
-                "recycler" => active.recycler_count = Set(p.recycler_count + qty),
-                "spy_probe" => active.spy_probe_count = Set(p.spy_probe_count + qty),
-                "missile_launcher" => active.missile_launcher_count = Set(p.missile_launcher_count + qty),
-                "plasma_turret" => active.plasma_turret_count = Set(p.plasma_turret_count + qty),
-                _ => {}
+            if units::is_known(&fleet_str) {
+                units::add(&txn, id, &fleet_str, qty).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
             }
             active.shipyard_construction_end = Set(None);
             active.pending_fleet_type = Set(None);
@@ -201,44 +227,148 @@ async fn get_planet_handler(
         }
     }
 
-    let updated_model = active.update(&state.db).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    Ok(Json(updated_model))
+    let mut updated_model = active.update(&txn).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some((metal, crystal, deuterium)) = unsettled {
+        updated_model.metal_amount = metal;
+        updated_model.crystal_amount = crystal;
+        updated_model.deuterium_amount = deuterium;
+    }
+    let inventory = units::load(&txn, id).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let lv = levels::load(&txn, &updated_model).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    txn.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let unread_notifications = notification::count_unread(&state.db, id).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let fields_used = game_logic::get_fields_used(&lv);
+    Ok(Json(PlanetView { planet: updated_model, units: inventory, levels: lv, unread_notifications, fields_used }))
 }
 
-async fn clear_report_handler(
+// Active ou quitte le mode vacances, pour toutes les planètes du joueur à la fois
+async fn vacation_handler(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    let p = match planet::Entity::find_by_id(id).one(&state.db).await {
-        Ok(Some(p)) => p,
-        _ => return StatusCode::NOT_FOUND,
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let owner_id = match planet::Entity::find_by_id(id).one(&txn).await {
+        Ok(Some(p)) => p.owner_id,
+        _ => return (StatusCode::NOT_FOUND, Json(json!({"error": "Planet not found"}))).into_response(),
+    };
+    let planets = match locks::lock_owner_planets(&txn, owner_id).await {
+        Ok(planets) => planets,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
     };
 
-    let mut active: planet::ActiveModel = p.into();
-    active.unread_report = Set(None);
-    
-    let _ = active.update(&state.db).await;
-    StatusCode::OK
+    let now = Utc::now().naive_utc();
+    let mut changes: Vec<planet::ActiveModel> = Vec::with_capacity(planets.len());
+
+    if planets.iter().any(|p| p.is_on_vacation()) {
+        // Sortie : seulement après la durée minimale
+        let min_end = planets.iter().filter_map(|p| p.vacation_min_end).max().unwrap_or(now);
+        if now < min_end {
+            return (StatusCode::CONFLICT, Json(json!({
+                "error": "Durée minimale du mode vacances non écoulée",
+                "available_at": min_end
+            }))).into_response();
+        }
+        for p in planets {
+            let mut active: planet::ActiveModel = p.into();
+            active.vacation_start = Set(None);
+            active.vacation_min_end = Set(None);
+            active.last_update = Set(now); // La production reprend maintenant
+            changes.push(active);
+        }
+    } else {
+        // Entrée : aucune flotte du joueur ne doit être en vol
+        let in_flight = fleet_entity::Entity::find()
+            .filter(fleet_entity::Column::PlanetId.is_in(planets.iter().map(|p| p.id).collect::<Vec<_>>()))
+            .count(&txn)
+            .await
+            .unwrap_or(0);
+        if in_flight > 0 {
+            return (StatusCode::CONFLICT, Json(json!({"error": "Des flottes sont encore en vol"}))).into_response();
+        }
+
+        // On solde la production accumulée avant de geler chaque planète
+        let min_end = now + Duration::hours(game_logic::VACATION_MIN_HOURS);
+        for p in planets {
+            let universe = match state.universes.require(&txn, p.universe_id).await {
+                Ok(u) => u,
+                Err(code) => return (code, Json(json!({"error": "Univers introuvable"}))).into_response(),
+            };
+            let lv = match levels::load(&txn, &p).await {
+                Ok(lv) => lv,
+                Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+            };
+            let metal = game_logic::calculate_resources(&universe, game_logic::ResourceType::Metal, levels::get(&lv, "metal"), p.temperature_max, p.metal_amount, p.last_update);
+            let crystal = game_logic::calculate_resources(&universe, game_logic::ResourceType::Crystal, levels::get(&lv, "crystal"), p.temperature_max, p.crystal_amount, p.last_update);
+            let deuterium = game_logic::calculate_resources(&universe, game_logic::ResourceType::Deuterium, levels::get(&lv, "deuterium"), p.temperature_max, p.deuterium_amount, p.last_update);
+            if ledger::record(&txn, p.id, ledger::Delta::since(&p, metal, crystal, deuterium), "production", None).await.is_err() {
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response();
+            }
+            let mut active: planet::ActiveModel = p.into();
+            active.metal_amount = Set(metal);
+            active.crystal_amount = Set(crystal);
+            active.deuterium_amount = Set(deuterium);
+            active.last_update = Set(now);
+            active.vacation_start = Set(Some(now));
+            active.vacation_min_end = Set(Some(min_end));
+            changes.push(active);
+        }
+    }
+
+    let mut updated = None;
+    for active in changes {
+        match active.update(&txn).await {
+            Ok(p) if p.id == id => updated = Some(p),
+            Ok(_) => {}
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response(),
+        }
+    }
+    match txn.commit().await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "status": "success", "planet": updated }))).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response(),
+    }
 }
 
 async fn upgrade_mine_handler(
     Path((id, type_mine)): Path<(Uuid, String)>,
     State(state): State<AppState>,
 ) -> Result<StatusCode, StatusCode> {
-    let p = planet::Entity::find_by_id(id).one(&state.db).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?.ok_or(StatusCode::NOT_FOUND)?;
-
-    if p.construction_end.is_some() { return Err(StatusCode::CONFLICT); }
-
-    let current_level = match type_mine.as_str() {
-        "metal" => p.metal_mine_level,
-        "crystal" => p.crystal_mine_level,
-        "deuterium" => p.deuterium_mine_level,
-        "energy_tech" => p.energy_tech_level,
-        "research" => p.research_lab_level,
-        "laser" => p.laser_battery_level,
-        "espionage" => p.espionage_tech_level,
-        _ => return Err(StatusCode::BAD_REQUEST),
-    };
+    // Verrou jusqu'au commit : deux ordres simultanés ne peuvent pas dépenser le même stock
+    let txn = state.db.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !levels::is_known(&type_mine) { return Err(StatusCode::BAD_REQUEST); }
+
+    // Recherche commune au compte : une seule à la fois, toutes planètes confondues.
+    // Toutes les planètes du joueur restent verrouillées : deux ordres lancés depuis
+    // deux planètes ne peuvent pas passer la vérification en même temps.
+    let mut researching = false;
+    if levels::is_research(&type_mine) {
+        let owner_id = planet::Entity::find_by_id(id)
+            .one(&txn)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?
+            .owner_id;
+        researching = locks::lock_owner_planets(&txn, owner_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .iter()
+            .any(|o| o.construction_type.as_deref().is_some_and(levels::is_research));
+    }
+
+    let p = locks::lock_planet(&txn, id).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?.ok_or(StatusCode::NOT_FOUND)?;
+
+    if p.is_on_vacation() { return Err(StatusCode::FORBIDDEN); }
+    if p.construction_end.is_some() || researching { return Err(StatusCode::CONFLICT); }
+
+    let lv = levels::load(&txn, &p).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let current_level = levels::get(&lv, &type_mine);
+
+    if !levels::is_research(&type_mine) && game_logic::get_fields_used(&lv) >= p.fields_max {
+        // Une case par niveau de bâtiment : planète pleine, plus de construction
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
 
     let cost = game_logic::get_upgrade_cost(&type_mine, current_level + 1);
 
@@ -246,16 +376,22 @@ async fn upgrade_mine_handler(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    let build_time = game_logic::get_build_time(cost.metal, cost.crystal);
+    let universe = state.universes.require(&txn, p.universe_id).await?;
+    let build_time = game_logic::get_build_time(&universe, &type_mine, &cost);
+    ledger::record(&txn, id, ledger::Delta::spend(&cost), "construction", None)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let mut active: planet::ActiveModel = p.into();
     active.metal_amount = Set(active.metal_amount.unwrap() - cost.metal);
     active.crystal_amount = Set(active.crystal_amount.unwrap() - cost.crystal);
     active.deuterium_amount = Set(active.deuterium_amount.unwrap() - cost.deuterium);
+    ranking::add_points(&mut active, game_logic::get_upgrade_category(&type_mine), game_logic::get_cost_points(&cost));
     active.construction_type = Set(Some(type_mine));
     active.construction_end = Set(Some(Utc::now().naive_utc() + Duration::seconds(build_time)));
 
-    active.update(&state.db).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    active.update(&txn).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    txn.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     Ok(StatusCode::OK)
 }
 
@@ -263,248 +399,175 @@ async fn build_fleet_handler(
     Path((id, type_ship, qty)): Path<(Uuid, String, i32)>,
     State(state): State<AppState>,
 ) -> Result<StatusCode, StatusCode> {
-    let p = planet::Entity::find_by_id(id).one(&state.db).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?.ok_or(StatusCode::NOT_FOUND)?;
+    // Verrou jusqu'au commit : deux ordres simultanés ne peuvent pas dépenser le même stock
+    let txn = state.db.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let p = locks::lock_planet(&txn, id).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?.ok_or(StatusCode::NOT_FOUND)?;
 
+    if p.is_on_vacation() { return Err(StatusCode::FORBIDDEN); }
     if p.shipyard_construction_end.is_some() || qty <= 0 { return Err(StatusCode::CONFLICT); }
 
-    let (cost_m, cost_c) = match type_ship.as_str() {
-        "light_hunter" => { let s = game_logic::get_light_hunter_stats(); (s.0, s.1) },
-        "cruiser" => (20000.0, 7000.0),
-        "recycler" => (10000.0, 6000.0),
-        "spy_probe" => { let s = game_logic::get_spy_probe_stats(); (s.0, s.1) },
-        "missile_launcher" => { let s = game_logic::get_missile_launcher_stats(); (s.0, s.1) },
-        "plasma_turret" => { let s = game_logic::get_plasma_turret_stats(); (s.0, s.1) },
-        _ => return Err(StatusCode::BAD_REQUEST),
-    };
+    let (cost_m, cost_c) = game_logic::get_unit_cost(&type_ship).ok_or(StatusCode::BAD_REQUEST)?;
+
+    // Dômes de protection : un seul exemplaire par planète
+    if game_logic::get_defense_stats(&type_ship).is_some_and(|d| d.unique) {
+        let owned = units::load(&txn, id).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if qty > 1 || units::count(&owned, &type_ship) > 0 { return Err(StatusCode::CONFLICT); }
+    }
 
     let total_m = cost_m * qty as f64;
     let total_c = cost_c * qty as f64;
 
     if p.metal_amount < total_m || p.crystal_amount < total_c { return Err(StatusCode::BAD_REQUEST); }
 
-    let build_time = game_logic::get_ship_production_time(qty);
+    let universe = state.universes.require(&txn, p.universe_id).await?;
+    let build_time = game_logic::get_ship_production_time(&universe, qty);
+    let spent = game_logic::Cost { metal: total_m, crystal: total_c, deuterium: 0.0 };
+    ledger::record(&txn, id, ledger::Delta::spend(&spent), "shipyard", None)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let mut active: planet::ActiveModel = p.into();
     active.metal_amount = Set(active.metal_amount.unwrap() - total_m);
     active.crystal_amount = Set(active.crystal_amount.unwrap() - total_c);
+    ranking::add_points(&mut active, "military_built", (total_m + total_c) / 1000.0);
     active.pending_fleet_type = Set(Some(type_ship));
     active.pending_fleet_count = Set(qty);
     active.shipyard_construction_end = Set(Some(Utc::now().naive_utc() + Duration::seconds(build_time)));
 
-    active.update(&state.db).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    active.update(&txn).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    txn.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     Ok(StatusCode::OK)
 }
 
-async fn attack_handler(
+#[derive(Deserialize)]
+struct ExpeditionPayload {
+    ships: HashMap<String, i32>,
+    #[serde(default = "default_hold_hours")]
+    hold_hours: i32,
+}
+
+fn default_hold_hours() -> i32 { 1 }
+
+async fn expedition_handler(
+    Path(id): Path<Uuid>,
     State(state): State<AppState>,
-    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
-    Json(payload): Json<AttackPayload>,
-) -> impl IntoResponse {
+    Json(payload): Json<ExpeditionPayload>,
+) -> impl IntoResponse { 
     
-    let attacker_id_str = params.get("current_planet_id").unwrap_or(&String::new()).to_string();
-    let attacker_id = match Uuid::parse_str(&attacker_id_str) {
-        Ok(id) => id,
-        Err(_) => return (StatusCode::UNAUTHORIZED, Json(json!({"error": "ID Attaquant invalide"}))).into_response(),
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let p = match locks::lock_planet(&txn, id).await {
+        Ok(Some(found)) => found,
+        _ => return (StatusCode::NOT_FOUND, Json(json!({"error": "Planet not found"}))).into_response(),
     };
 
-    if attacker_id == payload.target_planet_id {
-        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Impossible de s'attaquer soi-même"}))).into_response();
+    // Plusieurs expéditions en parallèle, limitées par l'astrophysique
+    if p.is_on_vacation() {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Mode vacances actif : aucun ordre de flotte possible"}))).into_response();
     }
 
-    let att_planet = match planet::Entity::find_by_id(attacker_id).one(&state.db).await {
-        Ok(Some(p)) => p,
-        _ => return (StatusCode::NOT_FOUND, Json(json!({"error": "Attaquant introuvable"}))).into_response(),
+    let active_expeditions = match fleet_entity::Entity::find()
+        .filter(fleet_entity::Column::PlanetId.eq(id))
+        .filter(fleet_entity::Column::MissionType.eq("expedition"))
+        .count(&txn)
+        .await
+    {
+        Ok(n) => n as i32,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
     };
-
-    let def_planet = match planet::Entity::find_by_id(payload.target_planet_id).one(&state.db).await {
-        Ok(Some(p)) => p,
-        _ => return (StatusCode::NOT_FOUND, Json(json!({"error": "Cible introuvable"}))).into_response(),
+    let research = match levels::load_research(&txn, p.owner_id).await {
+        Ok(research) => research,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
     };
+    let max_expeditions = game_logic::get_max_expeditions(levels::get(&research, "astrophysics"));
+    if active_expeditions >= max_expeditions {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": format!("Limite d'expéditions atteinte ({}/{})", active_expeditions, max_expeditions)}))).into_response();
+    }
 
-    if payload.hunters > att_planet.light_hunter_count || payload.cruisers > att_planet.cruiser_count {
-        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Flotte insuffisante"}))).into_response();
+    if !(1..=8).contains(&payload.hold_hours) {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Durée de stationnement invalide (1 à 8h)"}))).into_response();
     }
-    
-    if payload.hunters <= 0 && payload.cruisers <= 0 {
+
+    // 1. Vérification de la composition choisie
+    let ships: HashMap<String, i32> = payload.ships.into_iter().filter(|(_, qty)| *qty > 0).collect();
+    if ships.is_empty() {
         return (StatusCode::BAD_REQUEST, Json(json!({"error": "Aucune flotte envoyée"}))).into_response();
     }
-
-    let def_resources = game_logic::Cost {
-        metal: def_planet.metal_amount,
-        crystal: def_planet.crystal_amount,
-        deuterium: def_planet.deuterium_amount,
+    let inventory = match units::load(&txn, id).await {
+        Ok(inventory) => inventory,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
     };
+    for (ship_type, qty) in &ships {
+        if !fleet::SHIP_TYPES.contains(&ship_type.as_str()) || *qty > units::count(&inventory, ship_type) {
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": "Flotte insuffisante"}))).into_response();
+        }
+    }
 
-    let result = game_logic::resolve_pvp(
-        payload.hunters, 
-        payload.cruisers, 
-        def_planet.light_hunter_count, 
-        def_planet.cruiser_count, 
-        def_planet.laser_battery_level, 
-        def_planet.missile_launcher_count, 
-        def_planet.plasma_turret_count, 
-        def_resources 
-    );
-
-    // --- LOG POUR LE DÉFENSEUR ---
-    let log_def = combat_log::ActiveModel {
-        id: Set(Uuid::new_v4()),
-        planet_id: Set(def_planet.id),
-        target_name: Set(att_planet.name.clone()),
-        mission_type: Set("defense".to_string()),
-        result: Set(if result.winner == "defender" { "victory".to_string() } else { "defeat".to_string() }),
-        loot_metal: Set(-result.loot.metal),
-        loot_crystal: Set(-result.loot.crystal),
-        ships_lost: Set(result.defender_losses),
-        date: Set(Utc::now().naive_utc()),
+    // 2. Horaires : aller vers la position 16 du système, stationnement, retour
+    let universe = match state.universes.require(&txn, p.universe_id).await {
+        Ok(u) => u,
+        Err(code) => return (code, Json(json!({"error": "Univers introuvable"}))).into_response(),
     };
-    let _ = log_def.insert(&state.db).await;
-
-    // Mise à jour Défenseur
-    let mut def_active: planet::ActiveModel = def_planet.clone().into();
-    def_active.metal_amount = Set(def_active.metal_amount.unwrap() - result.loot.metal);
-    def_active.crystal_amount = Set(def_active.crystal_amount.unwrap() - result.loot.crystal);
-    def_active.light_hunter_count = Set(std::cmp::max(0, def_active.light_hunter_count.unwrap() - result.defender_losses));
-    
-    let percent_loss = if result.winner == "attacker" { 0.6 } else { 0.1 };
-    def_active.missile_launcher_count = Set((def_planet.missile_launcher_count as f64 * (1.0 - percent_loss)) as i32);
-    def_active.plasma_turret_count = Set((def_planet.plasma_turret_count as f64 * (1.0 - percent_loss)) as i32);
-
-    // Ajout des débris pour le défenseur
-    def_active.debris_metal = Set(def_planet.debris_metal + result.debris.metal);
-    def_active.debris_crystal = Set(def_planet.debris_crystal + result.debris.crystal);
-
-    let defender_report = json!({
-        "winner": result.winner, 
-        "log": result.log,
-        "loot": result.loot.metal + result.loot.crystal,
-        "losses": { "light_hunter": result.defender_losses, "cruiser": 0 },
-        "is_defense": true
-    });
-    def_active.unread_report = Set(Some(to_string(&defender_report).unwrap()));
-    let _ = def_active.update(&state.db).await;
+    let now = Utc::now().naive_utc();
+    let distance = game_logic::get_distance((p.galaxy, p.system, p.position), (p.galaxy, p.system, 16));
+    let fuel = game_logic::get_fuel_consumption(&ships, distance);
+    if fuel > p.deuterium_amount {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": format!("Deutérium insuffisant pour le vol ({:.0} requis)", fuel)}))).into_response();
+    }
+    let flight = game_logic::get_flight_time(&universe, distance, fleet::slowest_speed(&ships));
+    let hold = universe.expedition_hold(payload.hold_hours);
+    let arrival = now + Duration::seconds(flight);
+    let hold_end = arrival + Duration::seconds(hold);
+    let expected_return = hold_end + Duration::seconds(flight);
 
-    // --- LOG POUR L'ATTAQUANT ---
-    let log_att = combat_log::ActiveModel {
+    let movement = fleet_entity::ActiveModel {
         id: Set(Uuid::new_v4()),
-        planet_id: Set(att_planet.id),
-        target_name: Set(def_planet.name.clone()),
-        mission_type: Set("attack".to_string()),
-        result: Set(if result.winner == "attacker" { "victory".to_string() } else { "defeat".to_string() }),
-        loot_metal: Set(result.loot.metal),
-        loot_crystal: Set(result.loot.crystal),
-        ships_lost: Set(result.attacker_losses),
-        date: Set(Utc::now().naive_utc()),
-    };
-    let _ = log_att.insert(&state.db).await;
-
-    // Mise à jour Attaquant
-    let mut att_active: planet::ActiveModel = att_planet.into();
-    att_active.metal_amount = Set(att_active.metal_amount.unwrap() + result.loot.metal);
-    att_active.crystal_amount = Set(att_active.crystal_amount.unwrap() + result.loot.crystal);
-    att_active.light_hunter_count = Set(att_active.light_hunter_count.unwrap() - result.attacker_losses);
-    let _ = att_active.update(&state.db).await;
-
-    (StatusCode::OK, Json(json!({ "status": "success", "report": result }))).into_response()
-}
-
-async fn expedition_handler(
-    Path(id): Path<Uuid>,
-    State(state): State<AppState>,
-) -> impl IntoResponse { 
-    
-    let p_res = planet::Entity::find_by_id(id).one(&state.db).await;
-    let p = match p_res {
-        Ok(Some(found)) => found,
-        _ => return (StatusCode::NOT_FOUND, Json(json!({"error": "Planet not found"}))).into_response(),
+        planet_id: Set(id),
+        mission_type: Set("expedition".to_string()),
+        status: Set("outbound".to_string()),
+        target_galaxy: Set(p.galaxy),
+        target_system: Set(p.system),
+        target_position: Set(16),
+        ships: Set(to_string(&ships).unwrap()),
+        departure_time: Set(now),
+        arrival_time: Set(arrival),
+        hold_hours: Set(payload.hold_hours),
+        hold_end: Set(hold_end),
+        return_time: Set(expected_return),
+        ..Default::default()
     };
 
-    if let Some(date) = p.expedition_end {
-        if date > Utc::now().naive_utc() {
-             return (StatusCode::BAD_REQUEST, Json(json!({"error": "Expedition active"}))).into_response();
-        }
-    }
-
+    // 3. Les vaisseaux quittent la planète et restent bloqués jusqu'au retour
     let mut active: planet::ActiveModel = p.clone().into();
-    let mut loot = 0.0;
-    let mut logs: Vec<String> = Vec::new();
-    let winner; 
-    let mut lost_hunters = 0;
-    let mut lost_cruisers = 0;
-
-    let combat_triggered = rand::thread_rng().gen_bool(0.3);
-
-    if combat_triggered {
-        logs.push("⚠️ RADAR : Signature hostile détectée.".to_string());
-        
-        let combat_res = game_logic::simulate_combat(
-            p.light_hunter_count + p.cruiser_count, 
-            p.laser_battery_level
-        );
-
-        if combat_res.victory {
-            winner = "player";
-            loot = 5000.0 * (game_logic::SPEED_FACTOR / 100.0);
-            logs.push(format!("RESULTAT : {}", combat_res.message));
-            logs.push(format!("PILLAGE : +{:.0} Métal récupéré.", loot));
-
-            lost_hunters = combat_res.ships_lost; 
-            if lost_hunters > p.light_hunter_count { lost_hunters = p.light_hunter_count; }
-        } else {
-            winner = "pirates";
-            logs.push(format!("RESULTAT : {}", combat_res.message));
-            lost_hunters = (p.light_hunter_count as f64 * 0.5) as i32; 
-            lost_cruisers = (p.cruiser_count as f64 * 0.3) as i32;     
-        }
-        
-        active.light_hunter_count = Set(p.light_hunter_count - lost_hunters);
-        active.cruiser_count = Set(p.cruiser_count - lost_cruisers);
-        active.metal_amount = Set(p.metal_amount + loot);
+    active.deuterium_amount = Set(p.deuterium_amount - fuel);
+    // expedition_end indique le dernier retour attendu (affichage uniquement)
+    active.expedition_end = Set(Some(p.expedition_end.map_or(expected_return, |end| end.max(expected_return))));
 
-    } else {
-        winner = "player"; 
-        loot = 50000.0; 
-        logs.push("SCAN : Secteur calme.".to_string());
-        logs.push(format!("DECOUVERTE : Gisement trouvé (+{:.0} Métal).", loot));
-        active.metal_amount = Set(p.metal_amount + loot);
-    }
-
-    let duration = std::cmp::max(1, (600.0 / game_logic::SPEED_FACTOR) as i64);
-    active.expedition_end = Set(Some(Utc::now().naive_utc() + Duration::seconds(duration)));
-    
-    if let Err(_) = active.update(&state.db).await {
+    let movement = match movement.insert(&txn).await {
+        Ok(m) => m,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response(),
+    };
+    let burned = ledger::record(&txn, id, ledger::Delta { deuterium: -fuel, ..Default::default() }, "fuel", Some(movement.id)).await;
+    if burned.is_err() || units::add_all(&txn, id, &ships, -1).await.is_err() {
         return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response();
     }
-    
-    let updated_planet = planet::Entity::find_by_id(id).one(&state.db).await.unwrap().unwrap();
-
-    let log_exp = combat_log::ActiveModel {
-        id: Set(Uuid::new_v4()),
-        planet_id: Set(id),
-        target_name: Set("Secteur Inconnu".to_string()),
-        mission_type: Set("expedition".to_string()),
-        result: Set(winner.to_string()),
-        loot_metal: Set(loot),
-        loot_crystal: Set(0.0),
-        ships_lost: Set(lost_hunters + lost_cruisers),
-        date: Set(Utc::now().naive_utc()),
+    let updated_planet = match active.update(&txn).await {
+        Ok(updated) => updated,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response(),
     };
-    let _ = log_exp.insert(&state.db).await;
+    if txn.commit().await.is_err() {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "DB Update Error"}))).into_response();
+    }
 
-    let response = json!({
+    (StatusCode::OK, Json(json!({
+        "status": "success",
         "planet": updated_planet,
-        "report": {
-            "winner": winner,
-            "log": logs,
-            "loot": loot,
-            "losses": {
-                "light_hunter": lost_hunters,
-                "cruiser": lost_cruisers
-            }
-        }
-    });
-
-    (StatusCode::OK, Json(response)).into_response()
+        "fleet": movement,
+        "active_expeditions": active_expeditions + 1,
+        "max_expeditions": max_expeditions
+    }))).into_response()
 }
 
 async fn get_reports_handler(
@@ -536,21 +599,48 @@ async fn spy_handler(
     let attacker_id_str = params.get("current_planet_id").unwrap_or(&String::new()).to_string();
     let attacker_id = Uuid::parse_str(&attacker_id_str).unwrap_or_default();
     
-    let att_planet_opt = planet::Entity::find_by_id(attacker_id).one(&state.db).await.unwrap();
-    let def_planet_opt = planet::Entity::find_by_id(payload.target_planet_id).one(&state.db).await.unwrap();
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let att_planet = match locks::lock_planet(&txn, attacker_id).await {
+        Ok(Some(p)) => p,
+        Ok(None) => return (StatusCode::UNAUTHORIZED, Json(json!({"error": "Attaquant inconnu"}))).into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let def_planet = match planet::Entity::find_by_id(payload.target_planet_id).one(&txn).await {
+        Ok(Some(p)) => p,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({"error": "Cible inconnue"}))).into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
 
-    let att_planet = match att_planet_opt { Some(p) => p, None => return (StatusCode::UNAUTHORIZED, Json(json!({"error": "Attaquant inconnu"}))).into_response() };
-    let def_planet = match def_planet_opt { Some(p) => p, None => return (StatusCode::NOT_FOUND, Json(json!({"error": "Cible inconnue"}))).into_response() };
+    if att_planet.is_on_vacation() {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Mode vacances actif : aucun ordre de flotte possible"}))).into_response();
+    }
+    if def_planet.is_on_vacation() {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Cible en mode vacances"}))).into_response();
+    }
+    if def_planet.universe_id != att_planet.universe_id {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Cible dans un autre univers"}))).into_response();
+    }
 
-    if att_planet.spy_probe_count < 1 {
+    let (att_units, def_units) = match (units::load(&txn, att_planet.id).await, units::load(&txn, def_planet.id).await) {
+        (Ok(att), Ok(def)) => (att, def),
+        _ => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    if units::count(&att_units, "spy_probe") < 1 {
         return (StatusCode::BAD_REQUEST, Json(json!({"error": "Aucune sonde disponible"}))).into_response();
     }
 
-    let mut att_active: planet::ActiveModel = att_planet.clone().into();
-    att_active.spy_probe_count = Set(att_planet.spy_probe_count - 1);
-    let _ = att_active.update(&state.db).await;
+    if units::add(&txn, att_planet.id, "spy_probe", -1).await.is_err() {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response();
+    }
 
-    let tech_diff = att_planet.espionage_tech_level - def_planet.espionage_tech_level;
+    let (att_research, def_research) = match (levels::load_research(&txn, att_planet.owner_id).await, levels::load_research(&txn, def_planet.owner_id).await) {
+        (Ok(att), Ok(def)) => (att, def),
+        _ => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let tech_diff = levels::get(&att_research, "espionage") - levels::get(&def_research, "espionage");
     
     let mut detection = "none";
     let mut resources = None;
@@ -568,29 +658,67 @@ async fn spy_handler(
     
     if tech_diff >= 1 { 
         detection = "fleet";
-        let mut fleet_map = HashMap::new();
-        fleet_map.insert("light_hunter".to_string(), def_planet.light_hunter_count);
-        fleet_map.insert("cruiser".to_string(), def_planet.cruiser_count);
-        fleet_map.insert("recycler".to_string(), def_planet.recycler_count);
-        fleet_map.insert("spy_probe".to_string(), def_planet.spy_probe_count);
+        let fleet_map: HashMap<String, i32> = fleet::SHIP_TYPES
+            .iter()
+            .map(|ship_type| (ship_type.to_string(), units::count(&def_units, ship_type)))
+            .collect();
         fleet = Some(fleet_map);
     }
 
     if tech_diff >= 2 { 
         detection = "full";
-        defense = Some(def_planet.missile_launcher_count + def_planet.plasma_turret_count); 
+        let defense_map: HashMap<String, i32> = units::DEFENSE_TYPES
+            .iter()
+            .map(|defense_type| (defense_type.to_string(), units::count(&def_units, defense_type)))
+            .collect();
+        defense = Some(defense_map);
     }
 
+    let report = json!({
+        "success": true,
+        "target_name": def_planet.name,
+        "tech_difference": tech_diff,
+        "detection_level": detection,
+        "resources": resources,
+        "fleet": fleet,
+        "defense": defense
+    });
+    let coords = format!("[{}:{}:{}]", def_planet.galaxy, def_planet.system, def_planet.position);
+    let bus = state.events.deferred();
+    let delivered = messaging::notify(
+        &txn,
+        &bus,
+        att_planet.id,
+        "espionage",
+        &format!("Espionnage de {} {}", def_planet.name, coords),
+        format!("Rapport de sonde : niveau de détection « {} ».", detection),
+        Some(&report),
+    ).await;
+    if delivered.is_err() {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response();
+    }
+    if !def_planet.is_npc {
+        let delivered = messaging::notify(
+            &txn,
+            &bus,
+            def_planet.id,
+            "espionage",
+            "Activité d'espionnage",
+            format!("Une sonde de {} a été repérée au-dessus de votre planète {}.", att_planet.name, coords),
+            None,
+        ).await;
+        if delivered.is_err() {
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response();
+        }
+    }
+    if txn.commit().await.is_err() {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response();
+    }
+    bus.flush();
+
     (StatusCode::OK, Json(json!({
         "status": "success",
-        "report": {
-            "success": true,
-            "tech_difference": tech_diff,
-            "detection_level": detection,
-            "resources": resources,
-            "fleet": fleet,
-            "defense": defense
-        }
+        "report": report
     }))).into_response()
 }
 
@@ -609,26 +737,51 @@ async fn recycle_handler(
     let current_id_str = params.get("current_planet_id").unwrap_or(&String::new()).to_string();
     let current_id = Uuid::parse_str(&current_id_str).unwrap_or_default();
 
-    // Utilisation de into_active_model()
-    let mut att_planet = match planet::Entity::find_by_id(current_id).one(&state.db).await.unwrap() {
-        Some(p) => p.into_active_model(),
+    // Recycleur et champ de débris verrouillés : un même champ ne peut pas être récolté deux fois
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let (att_res, target_res) = match locks::lock_planet_pair(&txn, current_id, payload.target_planet_id).await {
+        Ok(pair) => pair,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+
+    let att_model = match att_res {
+        Some(p) => p,
         None => return (StatusCode::UNAUTHORIZED, Json(json!({"error": "Planète inconnue"}))).into_response(),
     };
+    if att_model.is_on_vacation() {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Mode vacances actif : aucun ordre de flotte possible"}))).into_response();
+    }
+
+    // Utilisation de into_active_model()
+    let mut att_planet = att_model.into_active_model();
 
-    let target_res = planet::Entity::find_by_id(payload.target_planet_id).one(&state.db).await.unwrap();
     let mut target_planet = match target_res {
         Some(p) => p.into_active_model(),
         None => return (StatusCode::NOT_FOUND, Json(json!({"error": "Cible inconnue"}))).into_response(),
     };
 
+    if att_planet.universe_id.clone().unwrap() != target_planet.universe_id.clone().unwrap() {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Cible dans un autre univers"}))).into_response();
+    }
+
     // 1. Vérification Flotte
-    let current_recyclers = att_planet.recycler_count.clone().unwrap();
+    let current_recyclers = match units::load(&txn, current_id).await {
+        Ok(inventory) => units::count(&inventory, "recycler"),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
     if payload.recyclers > current_recyclers || payload.recyclers <= 0 {
          return (StatusCode::BAD_REQUEST, Json(json!({"error": "Recycleurs insuffisants"}))).into_response();
     }
 
-    // 2. Capacité (20k par recycleur)
-    let capacity = (payload.recyclers as f64) * 20000.0;
+    // 2. Capacité (catalogue + technologie hyperespace)
+    let hyperspace_level = match levels::load_research(&txn, att_planet.owner_id.clone().unwrap()).await {
+        Ok(research) => levels::get(&research, "hyperspace"),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+    let capacity = game_logic::get_cargo_capacity("recycler", payload.recyclers, hyperspace_level);
     
     // 3. Récupération
     let debris_m = target_planet.debris_metal.clone().unwrap();
@@ -642,38 +795,38 @@ async fn recycle_handler(
          }))).into_response();
     }
 
-    // Logique simple : on prend tout ce qu'on peut, priorité Métal puis Cristal
-    let mut harvested_m = 0.0;
-    let mut harvested_c = 0.0;
-    let mut remaining_capacity = capacity;
+    // Récolte proportionnelle : la soute est répartie selon la composition du champ
+    let harvested_total = f64::min(total_debris, capacity);
+    let harvested_m = harvested_total * (debris_m / total_debris);
+    let harvested_c = harvested_total - harvested_m;
 
-    // Récolte Métal
-    if debris_m > 0.0 {
-        let take = f64::min(debris_m, remaining_capacity);
-        harvested_m = take;
-        remaining_capacity -= take;
-        target_planet.debris_metal = Set(debris_m - take);
-    }
+    let remaining_m = f64::max(0.0, debris_m - harvested_m);
+    let remaining_c = f64::max(0.0, debris_c - harvested_c);
+    target_planet.debris_metal = Set(remaining_m);
+    target_planet.debris_crystal = Set(remaining_c);
 
-    // Récolte Cristal (s'il reste de la place)
-    if debris_c > 0.0 && remaining_capacity > 0.0 {
-        let take = f64::min(debris_c, remaining_capacity);
-        harvested_c = take;
-        target_planet.debris_crystal = Set(debris_c - take);
-    }
+    // Recycleurs nécessaires pour vider le reste du champ
+    let unit_capacity = game_logic::get_cargo_capacity("recycler", 1, hyperspace_level);
+    let recyclers_needed = if unit_capacity > 0.0 { ((remaining_m + remaining_c) / unit_capacity).ceil() as i32 } else { 0 };
 
     // 4. Mise à jour DB
     // On crédite l'attaquant
     att_planet.metal_amount = Set(att_planet.metal_amount.unwrap() + harvested_m);
     att_planet.crystal_amount = Set(att_planet.crystal_amount.unwrap() + harvested_c);
 
-    let _ = att_planet.update(&state.db).await;
-    let _ = target_planet.update(&state.db).await;
+    let harvested = game_logic::Cost { metal: harvested_m, crystal: harvested_c, deuterium: 0.0 };
+    let recorded = ledger::record(&txn, current_id, ledger::Delta::gain(&harvested), "recycling", Some(payload.target_planet_id)).await;
+    if recorded.is_err() || att_planet.update(&txn).await.is_err() || target_planet.update(&txn).await.is_err() || txn.commit().await.is_err() {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response();
+    }
 
     (StatusCode::OK, Json(json!({
         "status": "success",
         "message": format!("Recyclage terminé. +{:.0} Métal, +{:.0} Cristal", harvested_m, harvested_c),
-        "harvested": { "metal": harvested_m, "crystal": harvested_c }
+        "harvested": { "metal": harvested_m, "crystal": harvested_c },
+        "remaining": { "metal": remaining_m, "crystal": remaining_c },
+        "capacity": capacity,
+        "recyclers_needed": recyclers_needed
     }))).into_response()
 }
 
@@ -686,6 +839,10 @@ struct GalaxySlot {
     owner_name: Option<String>, // On utilise le nom de la planète comme nom de joueur pour l'instant
     has_debris: bool,
     is_me: bool,
+    is_npc: bool,
+    protection: Option<&'static str>, // "newbie" ou "out_of_range" vis-à-vis du joueur courant
+    on_vacation: bool,
+    alliance_tag: Option<String>,
 }
 
 // Handler GET /galaxy/:galaxy_id/:system_id
@@ -695,30 +852,61 @@ async fn get_galaxy_handler(
     Query(params): Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
     
+    let universe = match state.universes.resolve(&state.db, &params).await {
+        Ok(u) => u,
+        Err(code) => return (code, Json(json!({"error": "Univers introuvable"}))).into_response(),
+    };
+    if !universe.contains(galaxy_id, system_id) {
+        return (StatusCode::NOT_FOUND, Json(json!({"error": "Coordonnées hors de l'univers"}))).into_response();
+    }
+
     let current_id_str = params.get("current_planet_id").unwrap_or(&String::new()).to_string();
     let current_id = Uuid::parse_str(&current_id_str).unwrap_or_default();
 
     // 1. Récupérer toutes les planètes de ce système
     let planets = planet::Entity::find()
+        .filter(planet::Column::UniverseId.eq(universe.id))
         .filter(planet::Column::Galaxy.eq(galaxy_id))
         .filter(planet::Column::System.eq(system_id))
         .all(&state.db)
         .await
         .unwrap_or_default();
 
-    // 2. Construire la liste des 15 positions (vides ou occupées)
+    let my_score = planet::Entity::find_by_id(current_id)
+        .one(&state.db)
+        .await
+        .ok()
+        .flatten()
+        .map(|p| game_logic::get_score(&p));
+
+    let tags = alliance::get_tags(&state.db, planets.iter().map(|p| p.id).collect()).await;
+
+    // 2. Construire la liste des positions (vides ou occupées)
     let mut slots: Vec<GalaxySlot> = Vec::new();
 
-    for pos in 1..=15 {
+    for pos in 1..=game_logic::POSITIONS_PER_SYSTEM {
         // Chercher si une planète existe à cette position
         if let Some(p) = planets.iter().find(|p| p.position == pos) {
             slots.push(GalaxySlot {
                 position: pos,
                 planet_id: Some(p.id),
                 planet_name: Some(p.name.clone()),
-                owner_name: Some(p.name.clone()), // Simplification
+                owner_name: Some(if p.is_npc { "Pirates".to_string() } else { p.name.clone() }), // Simplification
                 has_debris: p.debris_metal > 0.0 || p.debris_crystal > 0.0,
                 is_me: p.id == current_id,
+                is_npc: p.is_npc,
+                protection: if p.is_npc || p.id == current_id {
+                    None
+                } else {
+                    let score = game_logic::get_score(p);
+                    match my_score {
+                        Some(mine) => state.protection.check(mine, score),
+                        None if state.protection.is_newbie(score) => Some("newbie"),
+                        None => None,
+                    }
+                },
+                on_vacation: p.is_on_vacation(),
+                alliance_tag: tags.get(&p.id).cloned(),
             });
         } else {
             // Emplacement vide (colonisable plus tard)
@@ -729,11 +917,15 @@ async fn get_galaxy_handler(
                 owner_name: None,
                 has_debris: false,
                 is_me: false,
+                is_npc: false,
+                protection: None,
+                on_vacation: false,
+                alliance_tag: None,
             });
         }
     }
 
-    Json(slots)
+    Json(slots).into_response()
 }
 
 // Structure légère pour la carte
@@ -751,11 +943,20 @@ async fn get_galaxy_scan_handler(
     Query(params): Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
     
+    let universe = match state.universes.resolve(&state.db, &params).await {
+        Ok(u) => u,
+        Err(code) => return (code, Json(json!({"error": "Univers introuvable"}))).into_response(),
+    };
+    if !universe.contains(galaxy_id, 1) {
+        return (StatusCode::NOT_FOUND, Json(json!({"error": "Galaxie hors de l'univers"}))).into_response();
+    }
+
     let current_id_str = params.get("current_planet_id").unwrap_or(&String::new()).to_string();
     let current_id = Uuid::parse_str(&current_id_str).unwrap_or_default();
 
     // On récupère toutes les planètes de la galaxie (optimisable avec raw sql plus tard)
     let planets = planet::Entity::find()
+        .filter(planet::Column::UniverseId.eq(universe.id))
         .filter(planet::Column::Galaxy.eq(galaxy_id))
         .all(&state.db)
         .await
@@ -778,5 +979,5 @@ async fn get_galaxy_scan_handler(
 
     // On transforme la map en vecteur pour le JSON
     let results: Vec<SystemSummary> = systems_map.into_values().collect();
-    Json(results)
+    Json(results).into_response()
 }
\ No newline at end of file
@@ -0,0 +1,259 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::HeaderName, StatusCode},
+    response::{IntoResponse, Json},
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    sea_query::Expr,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{message, planet};
+use crate::events::{EventBus, GameEvent};
+use crate::AppState;
+
+pub const FOLDERS: [&str; 6] = ["personal", "combat", "espionage", "expedition", "alliance", "system"];
+const SYSTEM_SENDER: &str = "Commandement";
+
+type ApiResult = Result<Json<Value>, (StatusCode, Json<Value>)>;
+
+fn error(code: StatusCode, message: &str) -> (StatusCode, Json<Value>) {
+    (code, Json(json!({ "error": message })))
+}
+
+fn db_error(_: DbErr) -> (StatusCode, Json<Value>) {
+    error(StatusCode::INTERNAL_SERVER_ERROR, "Erreur DB")
+}
+
+fn current_planet_id(params: &HashMap<String, String>) -> Result<Uuid, (StatusCode, Json<Value>)> {
+    params.get("current_planet_id")
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| error(StatusCode::UNAUTHORIZED, "ID Planète invalide"))
+}
+
+// --- ENVOI ---
+
+// Dépose un message dans la boîte d'un commandant (sender = None pour un message système)
+#[allow(clippy::too_many_arguments)]
+pub async fn deliver(
+    db: &impl ConnectionTrait,
+    bus: &EventBus,
+    recipient_id: Uuid,
+    sender: Option<(Uuid, &str)>,
+    folder: &str,
+    subject: &str,
+    body: String,
+    report: Option<&Value>,
+) -> Result<(), DbErr> {
+    let sender_name = sender.map(|(_, name)| name).unwrap_or(SYSTEM_SENDER).to_string();
+    message::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        recipient_id: Set(recipient_id),
+        sender_id: Set(sender.map(|(id, _)| id)),
+        sender_name: Set(sender_name.clone()),
+        folder: Set(folder.to_string()),
+        subject: Set(subject.to_string()),
+        body: Set(body),
+        report: Set(report.map(|r| r.to_string())),
+        is_read: Set(false),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await?;
+
+    // Dans une transaction, passer un bus différé (EventBus::deferred) vidé après le commit
+    bus.publish(recipient_id, GameEvent::Message {
+        folder: folder.to_string(),
+        sender_name,
+        subject: subject.to_string(),
+    });
+    Ok(())
+}
+
+// Raccourci pour les rapports générés par le jeu
+pub async fn notify(db: &impl ConnectionTrait, bus: &EventBus, recipient_id: Uuid, folder: &str, subject: &str, body: String, report: Option<&Value>) -> Result<(), DbErr> {
+    deliver(db, bus, recipient_id, None, folder, subject, body, report).await
+}
+
+// Contrôle commun aux messages privés et aux circulaires
+pub fn validate(subject: &str, body: &str) -> Result<(), (StatusCode, Json<Value>)> {
+    if !(1..=100).contains(&subject.trim().chars().count()) || !(1..=2000).contains(&body.trim().chars().count()) {
+        return Err(error(StatusCode::BAD_REQUEST, "Sujet (1-100) ou message (1-2000) invalide"));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct SendPayload {
+    pub recipient_name: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// POST /messages?current_planet_id=...
+pub async fn send_message_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    Json(payload): Json<SendPayload>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    validate(&payload.subject, &payload.body)?;
+
+    let sender = planet::Entity::find_by_id(planet_id)
+        .one(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::UNAUTHORIZED, "Expéditeur inconnu"))?;
+    let recipient = planet::Entity::find()
+        .filter(planet::Column::Name.eq(payload.recipient_name.trim()))
+        .filter(planet::Column::UniverseId.eq(sender.universe_id)) // Pas de courrier entre univers
+        .filter(planet::Column::IsNpc.eq(false))
+        .one(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "Commandant introuvable"))?;
+    if recipient.id == sender.id {
+        return Err(error(StatusCode::BAD_REQUEST, "Impossible de s'écrire à soi-même"));
+    }
+
+    deliver(
+        &state.db,
+        &state.events,
+        recipient.id,
+        Some((sender.id, &sender.name)),
+        "personal",
+        payload.subject.trim(),
+        payload.body.trim().to_string(),
+        None,
+    )
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(json!({ "status": "success" })))
+}
+
+// --- CONSULTATION ---
+
+// GET /messages?current_planet_id=...&folder=combat&page=1&per_page=20
+pub async fn list_messages_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let planet_id = match current_planet_id(&params) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    let page = params.get("page").and_then(|s| s.parse::<u64>().ok()).unwrap_or(1).max(1);
+    let per_page = params.get("per_page").and_then(|s| s.parse::<u64>().ok()).unwrap_or(20).clamp(1, 100);
+
+    let mut query = message::Entity::find().filter(message::Column::RecipientId.eq(planet_id));
+    if let Some(folder) = params.get("folder") {
+        if !FOLDERS.contains(&folder.as_str()) {
+            return error(StatusCode::BAD_REQUEST, "Dossier inconnu").into_response();
+        }
+        query = query.filter(message::Column::Folder.eq(folder.as_str()));
+    }
+
+    let total = query.clone().count(&state.db).await.unwrap_or(0);
+    let messages = query
+        .order_by_desc(message::Column::CreatedAt)
+        .offset((page - 1) * per_page)
+        .limit(per_page)
+        .all(&state.db)
+        .await
+        .unwrap_or_default();
+
+    (
+        [(HeaderName::from_static("x-total-count"), total.to_string())],
+        Json(messages),
+    ).into_response()
+}
+
+// GET /messages/unread?current_planet_id=... (non lus par dossier)
+pub async fn unread_counts_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+
+    let unread = message::Entity::find()
+        .filter(message::Column::RecipientId.eq(planet_id))
+        .filter(message::Column::IsRead.eq(false))
+        .all(&state.db)
+        .await
+        .map_err(db_error)?;
+
+    let mut counts: HashMap<&str, usize> = FOLDERS.iter().map(|f| (*f, 0)).collect();
+    for m in &unread {
+        if let Some(count) = counts.get_mut(m.folder.as_str()) {
+            *count += 1;
+        }
+    }
+
+    Ok(Json(json!({ "total": unread.len(), "folders": counts })))
+}
+
+// --- LECTURE / SUPPRESSION ---
+
+async fn find_own(db: &DatabaseConnection, id: Uuid, planet_id: Uuid) -> Result<message::Model, (StatusCode, Json<Value>)> {
+    message::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(db_error)?
+        .filter(|m| m.recipient_id == planet_id)
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "Message introuvable"))
+}
+
+// POST /messages/:id/read?current_planet_id=...
+pub async fn mark_read_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let found = find_own(&state.db, id, planet_id).await?;
+
+    let mut active: message::ActiveModel = found.into();
+    active.is_read = Set(true);
+    active.update(&state.db).await.map_err(db_error)?;
+
+    Ok(Json(json!({ "status": "success" })))
+}
+
+// POST /messages/read-all?current_planet_id=...&folder=combat (tous les dossiers si absent)
+pub async fn mark_all_read_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+
+    let mut update = message::Entity::update_many()
+        .col_expr(message::Column::IsRead, Expr::value(true))
+        .filter(message::Column::RecipientId.eq(planet_id))
+        .filter(message::Column::IsRead.eq(false));
+    if let Some(folder) = params.get("folder") {
+        update = update.filter(message::Column::Folder.eq(folder.as_str()));
+    }
+    let result = update.exec(&state.db).await.map_err(db_error)?;
+
+    Ok(Json(json!({ "status": "success", "updated": result.rows_affected })))
+}
+
+// DELETE /messages/:id?current_planet_id=...
+pub async fn delete_message_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let planet_id = current_planet_id(&params)?;
+    let found = find_own(&state.db, id, planet_id).await?;
+    found.delete(&state.db).await.map_err(db_error)?;
+
+    Ok(Json(json!({ "status": "success" })))
+}
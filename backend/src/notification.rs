@@ -0,0 +1,147 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+    sea_query::Expr,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::notification;
+use crate::events::{EventBus, GameEvent};
+use crate::AppState;
+
+// Type d'alerte, qui détermine la forme du payload
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationKind {
+    Defense,    // Rapport de combat côté défenseur
+    Attack,     // Rapport d'attaque au retour de la flotte
+    Expedition, // Rapport d'expédition au retour de la flotte
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Defense => "defense_report",
+            NotificationKind::Attack => "attack_report",
+            NotificationKind::Expedition => "expedition_report",
+        }
+    }
+
+    // Rapport livré au retour d'une flotte
+    pub fn for_mission(mission_type: &str) -> Self {
+        match mission_type {
+            "expedition" => NotificationKind::Expedition,
+            _ => NotificationKind::Attack,
+        }
+    }
+}
+
+// Ajoute une alerte non lue à la boîte du joueur
+pub async fn push(db: &impl ConnectionTrait, bus: &EventBus, planet_id: Uuid, kind: NotificationKind, payload: &Value) -> Result<(), DbErr> {
+    notification::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        planet_id: Set(planet_id),
+        kind: Set(kind.as_str().to_string()),
+        payload: Set(payload.to_string()),
+        is_read: Set(false),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await?;
+
+    // Dans une transaction, passer un bus différé (EventBus::deferred) vidé après le commit
+    bus.publish(planet_id, GameEvent::Notification { kind: kind.as_str().to_string(), payload: payload.clone() });
+    Ok(())
+}
+
+pub async fn count_unread(db: &DatabaseConnection, planet_id: Uuid) -> Result<u64, DbErr> {
+    notification::Entity::find()
+        .filter(notification::Column::PlanetId.eq(planet_id))
+        .filter(notification::Column::IsRead.eq(false))
+        .count(db)
+        .await
+}
+
+// --- HANDLERS ---
+
+#[derive(Serialize)]
+pub struct NotificationView {
+    id: Uuid,
+    kind: String,
+    payload: Value,
+    is_read: bool,
+    created_at: NaiveDateTime,
+}
+
+// GET /planets/:id/notifications?unread_only=true
+pub async fn list_notifications_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<Vec<NotificationView>> {
+    let mut query = notification::Entity::find().filter(notification::Column::PlanetId.eq(id));
+    if params.get("unread_only").is_some_and(|v| v == "true") {
+        query = query.filter(notification::Column::IsRead.eq(false));
+    }
+
+    let items = query
+        .order_by_desc(notification::Column::CreatedAt)
+        .limit(50)
+        .all(&state.db)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|n| NotificationView {
+            id: n.id,
+            kind: n.kind,
+            payload: serde_json::from_str(&n.payload).unwrap_or(Value::Null),
+            is_read: n.is_read,
+            created_at: n.created_at,
+        })
+        .collect();
+
+    Json(items)
+}
+
+// POST /planets/:id/notifications/:notification_id/read
+pub async fn mark_read_handler(
+    Path((id, notification_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+) -> Result<Json<Value>, StatusCode> {
+    let found = notification::Entity::find_by_id(notification_id)
+        .one(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .filter(|n| n.planet_id == id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let mut active: notification::ActiveModel = found.into();
+    active.is_read = Set(true);
+    active.update(&state.db).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "status": "success" })))
+}
+
+// POST /planets/:id/notifications/read-all
+pub async fn mark_all_read_handler(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<Value>, StatusCode> {
+    let result = notification::Entity::update_many()
+        .col_expr(notification::Column::IsRead, Expr::value(true))
+        .filter(notification::Column::PlanetId.eq(id))
+        .filter(notification::Column::IsRead.eq(false))
+        .exec(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "status": "success", "updated": result.rows_affected })))
+}
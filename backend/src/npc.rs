@@ -0,0 +1,269 @@
+use chrono::{Duration, Utc};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, Set, TransactionTrait,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{fleet as fleet_entity, planet};
+use crate::events::{EventBus, GameEvent};
+use crate::universe::Universes;
+use crate::{fleet, game_logic, ledger, levels, locks, placement, units};
+
+const PLAYERS_PER_BASE: usize = 10; // Un repaire pour 10 joueurs par galaxie
+const SPAWN_SPREAD: i32 = 5;        // Écart max (en systèmes) entre un repaire et un joueur
+const RAID_RANGE: i32 = 10;         // Portée des raids en systèmes
+const RAID_CHANCE: f64 = 0.15;      // Probabilité de raid par cycle et par repaire
+const REBUILD_RATE: f64 = 0.1;      // Part de la garnison reconstruite par cycle
+const ACTIVE_HOURS: i64 = 24;       // Seuls les joueurs connectés récemment sont ciblés
+const BASE_MINES: [(&str, i32); 3] = [("metal", 8), ("crystal", 6), ("deuterium", 4)]; // Niveaux des mines d'un repaire
+
+// Lance la boucle des repaires pirates (NPC_TICK_SECONDS, 60 par défaut)
+pub fn spawn_npc_loop(db: DatabaseConnection, universes: Universes, protection: game_logic::ProtectionRules, bus: EventBus) {
+    let tick = std::env::var("NPC_TICK_SECONDS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(tick));
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_cycle(&db, &universes, protection, &bus).await {
+                println!("⚠️ Cycle pirate interrompu : {}", e);
+            }
+        }
+    });
+}
+
+async fn run_cycle(db: &DatabaseConnection, universes: &Universes, protection: game_logic::ProtectionRules, bus: &EventBus) -> Result<(), DbErr> {
+    // Les raids doivent atterrir même si personne ne consulte sa planète
+    fleet::process_fleets(db, bus, universes).await?;
+
+    let (bases, players): (Vec<planet::Model>, Vec<planet::Model>) = planet::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .partition(|p| p.is_npc);
+
+    ensure_bases(db, universes, &bases, &players).await?;
+
+    let bases = planet::Entity::find()
+        .filter(planet::Column::IsNpc.eq(true))
+        .all(db)
+        .await?;
+
+    // Un repaire à la fois, verrouillé : un joueur peut l'attaquer pendant la reconstruction
+    for base in bases {
+        let txn = db.begin().await?;
+        let Some(base) = locks::lock_planet(&txn, base.id).await? else {
+            continue;
+        };
+        let Some(universe) = &universes.get(&txn, base.universe_id).await? else {
+            continue;
+        };
+        let galaxy_players: Vec<&planet::Model> = players.iter()
+            .filter(|p| p.universe_id == base.universe_id && p.galaxy == base.galaxy)
+            .collect();
+        let base = rebuild_base(&txn, universe, base, &galaxy_players).await?;
+        let raid_bus = bus.deferred(); // Alerte envoyée seulement si le raid est bien enregistré
+        launch_raid(&txn, &raid_bus, universe, base, &galaxy_players, protection).await?;
+        txn.commit().await?;
+        raid_bus.flush();
+    }
+
+    Ok(())
+}
+
+// --- GARNISON ---
+
+// Garnison visée (chasseurs, croiseurs, lanceurs) selon le score moyen de la galaxie
+fn target_garrison(players: &[&planet::Model]) -> (i32, i32, i32) {
+    let avg_score = if players.is_empty() {
+        0
+    } else {
+        players.iter().map(|p| game_logic::get_score(p)).sum::<i32>() / players.len() as i32
+    };
+    // Un chasseur vaut 4 points : la garnison pèse environ la moitié du score moyen
+    let hunters = std::cmp::max(20, avg_score / 8);
+    (hunters, hunters / 5, hunters / 2)
+}
+
+// --- PLACEMENT ---
+
+async fn ensure_bases(db: &DatabaseConnection, universes: &Universes, bases: &[planet::Model], players: &[planet::Model]) -> Result<(), DbErr> {
+    // Galaxies peuplées, univers par univers
+    let mut by_galaxy: HashMap<(Uuid, i32), Vec<&planet::Model>> = HashMap::new();
+    for p in players {
+        by_galaxy.entry((p.universe_id, p.galaxy)).or_default().push(p);
+    }
+
+    for ((universe_id, galaxy), galaxy_players) in by_galaxy {
+        let Some(universe) = universes.get(db, universe_id).await? else {
+            continue;
+        };
+        let wanted = std::cmp::max(1, galaxy_players.len() / PLAYERS_PER_BASE);
+        let existing = bases.iter().filter(|b| b.universe_id == universe_id && b.galaxy == galaxy).count();
+
+        for _ in existing..wanted {
+            let (system, position) = {
+                let mut rng = rand::thread_rng();
+                let anchor = galaxy_players.choose(&mut rng).unwrap();
+                let system = (anchor.system + rng.gen_range(-SPAWN_SPREAD..=SPAWN_SPREAD)).clamp(1, universe.systems_per_galaxy);
+                (system, rng.gen_range(1..=game_logic::POSITIONS_PER_SYSTEM))
+            };
+
+            // Emplacement déjà occupé : on retentera au prochain cycle
+            let occupied = planet::Entity::find()
+                .filter(planet::Column::UniverseId.eq(universe_id))
+                .filter(planet::Column::Galaxy.eq(galaxy))
+                .filter(planet::Column::System.eq(system))
+                .filter(planet::Column::Position.eq(position))
+                .count(db)
+                .await?;
+            if occupied > 0 {
+                continue;
+            }
+
+            let (hunters, cruisers, missiles) = target_garrison(&galaxy_players);
+            let id = Uuid::new_v4();
+            let mut base = planet::ActiveModel {
+                id: Set(id),
+                owner_id: Set(Uuid::new_v4()),
+                universe_id: Set(universe_id),
+                name: Set(format!("Repaire Pirate {}", &id.simple().to_string()[..4].to_uppercase())),
+                password: Set(String::new()), // Aucun hash valide : connexion impossible
+                galaxy: Set(galaxy),
+                system: Set(system),
+                position: Set(position),
+                last_update: Set(Utc::now().naive_utc()),
+                is_npc: Set(true),
+                ..Default::default()
+            };
+            placement::roll_traits(&mut base, position);
+            match base.insert(db).await {
+                Ok(_) => {}
+                Err(e) if placement::is_slot_taken(&e) => continue, // Pris entre-temps par une inscription
+                Err(e) => return Err(e),
+            }
+            for (building_type, level) in BASE_MINES {
+                levels::set_building(db, id, building_type, level).await?;
+            }
+            units::set(db, id, "light_hunter", hunters).await?;
+            units::set(db, id, "cruiser", cruisers).await?;
+            units::set(db, id, "missile_launcher", missiles).await?;
+            println!("🏴‍☠️ Nouveau repaire pirate en [{}:{}:{}]", galaxy, system, position);
+        }
+    }
+
+    Ok(())
+}
+
+// --- RECONSTRUCTION ---
+
+// Production des mines et reconstruction progressive de la garnison
+async fn rebuild_base(db: &impl ConnectionTrait, universe: &game_logic::Universe, base: planet::Model, players: &[&planet::Model]) -> Result<planet::Model, DbErr> {
+    let (hunters, cruisers, missiles) = target_garrison(players);
+    let regrow = |current: i32, target: i32| std::cmp::min(target, current + (target as f64 * REBUILD_RATE).ceil() as i32).max(current);
+
+    let mut active: planet::ActiveModel = base.clone().into();
+    // Production soldée au même rythme qu'une planète consultée, pas à chaque cycle
+    let now = Utc::now().naive_utc();
+    if now.signed_duration_since(base.last_update).num_seconds() >= ledger::PRODUCTION_SETTLE_SECONDS {
+        let lv = levels::load(db, &base).await?;
+        let metal = game_logic::calculate_resources(universe, game_logic::ResourceType::Metal, levels::get(&lv, "metal"), base.temperature_max, base.metal_amount, base.last_update);
+        let crystal = game_logic::calculate_resources(universe, game_logic::ResourceType::Crystal, levels::get(&lv, "crystal"), base.temperature_max, base.crystal_amount, base.last_update);
+        let deuterium = game_logic::calculate_resources(universe, game_logic::ResourceType::Deuterium, levels::get(&lv, "deuterium"), base.temperature_max, base.deuterium_amount, base.last_update);
+        ledger::record(db, base.id, ledger::Delta::since(&base, metal, crystal, deuterium), "production", None).await?;
+        active.metal_amount = Set(metal);
+        active.crystal_amount = Set(crystal);
+        active.deuterium_amount = Set(deuterium);
+        active.last_update = Set(now);
+    }
+    let garrison = units::load(db, base.id).await?;
+    units::set(db, base.id, "light_hunter", regrow(units::count(&garrison, "light_hunter"), hunters)).await?;
+    units::set(db, base.id, "cruiser", regrow(units::count(&garrison, "cruiser"), cruisers)).await?;
+    units::set(db, base.id, "missile_launcher", regrow(units::count(&garrison, "missile_launcher"), missiles)).await?;
+
+    active.update(db).await
+}
+
+// --- RAIDS ---
+
+async fn launch_raid(db: &impl ConnectionTrait, bus: &EventBus, universe: &game_logic::Universe, base: planet::Model, players: &[&planet::Model], protection: game_logic::ProtectionRules) -> Result<(), DbErr> {
+    // Un seul raid en vol par repaire
+    let in_flight = fleet_entity::Entity::find()
+        .filter(fleet_entity::Column::PlanetId.eq(base.id))
+        .count(db)
+        .await?;
+    if in_flight > 0 {
+        return Ok(());
+    }
+
+    let active_since = Utc::now().naive_utc() - Duration::hours(ACTIVE_HOURS);
+    let target = {
+        let mut rng = rand::thread_rng();
+        if !rng.gen_bool(RAID_CHANCE) {
+            return Ok(());
+        }
+        let candidates: Vec<&&planet::Model> = players
+            .iter()
+            .filter(|p| (p.system - base.system).abs() <= RAID_RANGE && p.last_update >= active_since && !p.is_on_vacation())
+            .filter(|p| !protection.is_newbie(game_logic::get_score(p))) // Les débutants sont épargnés
+            .collect();
+        match candidates.choose(&mut rng) {
+            Some(p) => (**p).clone(),
+            None => return Ok(()),
+        }
+    };
+
+    // Taille du raid proportionnelle au score de la cible, dans la limite de la garnison
+    let score = game_logic::get_score(&target);
+    let garrison = units::load(db, base.id).await?;
+    let hunters = std::cmp::min(units::count(&garrison, "light_hunter"), std::cmp::max(5, score / 10));
+    let cruisers = std::cmp::min(units::count(&garrison, "cruiser"), score / 100);
+    if hunters + cruisers <= 0 {
+        return Ok(());
+    }
+
+    let mut ships = HashMap::new();
+    ships.insert("light_hunter".to_string(), hunters);
+    ships.insert("cruiser".to_string(), cruisers);
+    ships.retain(|_, qty| *qty > 0);
+
+    let now = Utc::now().naive_utc();
+    let distance = game_logic::get_distance((base.galaxy, base.system, base.position), (target.galaxy, target.system, target.position));
+    let arrival = now + Duration::seconds(game_logic::get_flight_time(universe, distance, fleet::slowest_speed(&ships)));
+
+    let raid_id = Uuid::new_v4();
+    let raid = fleet_entity::ActiveModel {
+        id: Set(raid_id),
+        planet_id: Set(base.id),
+        mission_type: Set("attack".to_string()),
+        status: Set("outbound".to_string()),
+        target_planet_id: Set(Some(target.id)),
+        target_galaxy: Set(target.galaxy),
+        target_system: Set(target.system),
+        target_position: Set(target.position),
+        ships: Set(serde_json::to_string(&ships).unwrap()),
+        departure_time: Set(now),
+        arrival_time: Set(arrival),
+        hold_end: Set(arrival),
+        return_time: Set(arrival + (arrival - now)),
+        ..Default::default()
+    };
+    raid.insert(db).await?;
+    units::add_all(db, base.id, &ships, -1).await?;
+    bus.publish(target.id, GameEvent::IncomingFleet {
+        fleet_id: raid_id,
+        origin_name: base.name.clone(),
+        mission_type: "attack".to_string(),
+        arrival_time: arrival,
+    });
+
+    println!("🏴‍☠️ {} lance un raid sur {} ({} chasseurs, {} croiseurs)", base.name, target.name, hunters, cruisers);
+    Ok(())
+}
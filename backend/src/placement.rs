@@ -0,0 +1,75 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, Set, SqlErr,
+};
+
+use crate::entities::planet;
+use crate::game_logic::{self, POSITIONS_PER_SYSTEM};
+
+pub const MAX_ATTEMPTS: usize = 5; // Tentatives de placement avant d'abandonner
+
+// Emplacement libre pour un nouveau joueur : la galaxie la moins peuplée de l'univers,
+// puis un des systèmes les moins peuplés de cette galaxie (tiré au hasard pour que deux
+// inscriptions simultanées ne visent pas la même case). None si l'univers est plein.
+pub async fn find_free_slot(db: &impl ConnectionTrait, universe: &game_logic::Universe) -> Result<Option<(i32, i32, i32)>, DbErr> {
+    let per_galaxy: Vec<(i32, i64)> = planet::Entity::find()
+        .select_only()
+        .column(planet::Column::Galaxy)
+        .column_as(planet::Column::Id.count(), "count")
+        .filter(planet::Column::UniverseId.eq(universe.id))
+        .group_by(planet::Column::Galaxy)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let population = |g: i32| per_galaxy.iter().find(|(galaxy, _)| *galaxy == g).map(|(_, n)| *n).unwrap_or(0);
+    let galaxy = (1..=universe.galaxies).min_by_key(|g| population(*g)).unwrap_or(1);
+
+    let taken: Vec<(i32, i32)> = planet::Entity::find()
+        .select_only()
+        .column(planet::Column::System)
+        .column(planet::Column::Position)
+        .filter(planet::Column::UniverseId.eq(universe.id))
+        .filter(planet::Column::Galaxy.eq(galaxy))
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let mut per_system = vec![0; universe.systems_per_galaxy as usize + 1];
+    for (system, _) in &taken {
+        if let Some(count) = per_system.get_mut(*system as usize) {
+            *count += 1;
+        }
+    }
+    let Some(least) = per_system[1..].iter().copied().min().filter(|n| *n < POSITIONS_PER_SYSTEM) else {
+        return Ok(None);
+    };
+    let systems: Vec<i32> = (1..=universe.systems_per_galaxy).filter(|s| per_system[*s as usize] == least).collect();
+
+    let mut rng = rand::thread_rng();
+    let Some(&system) = systems.choose(&mut rng) else {
+        return Ok(None);
+    };
+    let free: Vec<i32> = (1..=POSITIONS_PER_SYSTEM)
+        .filter(|p| !taken.contains(&(system, *p)))
+        .collect();
+    Ok(free.choose(&mut rng).map(|&position| (galaxy, system, position)))
+}
+
+// L'insertion a échoué parce que l'emplacement vient d'être pris (index unique des coordonnées)
+pub fn is_slot_taken(err: &DbErr) -> bool {
+    matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(msg)) if msg.contains("idx_planet_coords"))
+}
+
+// Taille et climat d'une nouvelle planète, tirés selon sa position
+pub fn roll_traits(planet: &mut planet::ActiveModel, position: i32) {
+    let mut rng = rand::thread_rng();
+    let (min_fields, max_fields) = game_logic::get_field_range(position);
+    let fields = rng.gen_range(min_fields..=max_fields);
+    let temperature_max = game_logic::get_base_temperature(position) + rng.gen_range(-20..=20);
+
+    planet.diameter = Set(game_logic::get_diameter(fields));
+    planet.fields_max = Set(fields);
+    planet.temperature_max = Set(temperature_max);
+    planet.temperature_min = Set(temperature_max - 40);
+}
@@ -0,0 +1,256 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::HeaderName, StatusCode},
+    response::{IntoResponse, Json},
+};
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::{planet, ranking};
+use crate::{alliance, game_logic, AppState};
+
+const RETENTION_DAYS: i64 = 30; // Historique conservé
+const INSERT_CHUNK: usize = 1000;
+
+#[derive(Serialize)]
+pub struct RankItem {
+    rank: i32,
+    planet_name: String,
+    score: i32,
+    is_me: bool,
+    id: Uuid,
+    protection: Option<&'static str>, // "newbie" ou "out_of_range" vis-à-vis du joueur courant
+    rank_change: Option<i32>,         // Places gagnées depuis l'instantané précédent (None si nouveau)
+    alliance_tag: Option<String>,
+}
+
+// --- POINTS ---
+
+// Crédite des points dans une catégorie (sur la valeur déjà présente dans l'ActiveModel)
+pub fn add_points(active: &mut planet::ActiveModel, category: &str, points: f64) {
+    match category {
+        "economy" => active.points_economy = Set(active.points_economy.clone().unwrap() + points),
+        "research" => active.points_research = Set(active.points_research.clone().unwrap() + points),
+        "military_built" => active.points_military_built = Set(active.points_military_built.clone().unwrap() + points),
+        "military_destroyed" => active.points_military_destroyed = Set(active.points_military_destroyed.clone().unwrap() + points),
+        "military_lost" => active.points_military_lost = Set(active.points_military_lost.clone().unwrap() + points),
+        _ => {}
+    }
+}
+
+// --- INSTANTANÉS ---
+
+// Lance la prise d'instantanés périodique (RANKING_SNAPSHOT_SECONDS, 600 par défaut)
+pub fn spawn_snapshot_loop(db: DatabaseConnection) {
+    let period = std::env::var("RANKING_SNAPSHOT_SECONDS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(600);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(period));
+        loop {
+            interval.tick().await;
+            if let Err(e) = take_snapshot(&db).await {
+                println!("⚠️ Instantané du classement impossible : {}", e);
+            }
+        }
+    });
+}
+
+// Un instantané est écrit en entier ou pas du tout
+pub async fn take_snapshot(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    let planets = planet::Entity::find()
+        .filter(planet::Column::IsNpc.eq(false))
+        .all(&txn)
+        .await?;
+    let now = Utc::now().naive_utc();
+
+    // Chaque univers a son propre classement
+    let mut by_universe: HashMap<Uuid, Vec<&planet::Model>> = HashMap::new();
+    for p in &planets {
+        by_universe.entry(p.universe_id).or_default().push(p);
+    }
+
+    for (universe_id, players) in by_universe {
+        for category in game_logic::SCORE_CATEGORIES {
+            let mut scored: Vec<(&planet::Model, f64)> = players
+                .iter()
+                .map(|p| (*p, game_logic::get_category_score(p, category)))
+                .collect();
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            let rows: Vec<ranking::ActiveModel> = scored
+                .iter()
+                .enumerate()
+                .map(|(i, (p, score))| ranking::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    planet_id: Set(p.id),
+                    planet_name: Set(p.name.clone()),
+                    universe_id: Set(universe_id),
+                    category: Set(category.to_string()),
+                    rank: Set(i as i32 + 1),
+                    score: Set(*score),
+                    snapshot_at: Set(now),
+                })
+                .collect();
+
+            for chunk in rows.chunks(INSERT_CHUNK) {
+                ranking::Entity::insert_many(chunk.to_vec()).exec(&txn).await?;
+            }
+        }
+    }
+
+    ranking::Entity::delete_many()
+        .filter(ranking::Column::SnapshotAt.lt(now - Duration::days(RETENTION_DAYS)))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await
+}
+
+// --- HANDLERS ---
+
+// GET /ranking?category=total&page=1&per_page=50&current_planet_id=...&universe_id=...
+pub async fn get_ranking_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+
+    let current_planet_id = params.get("current_planet_id")
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .unwrap_or_default();
+    let category = params.get("category").map(String::as_str).unwrap_or("total");
+    if !game_logic::SCORE_CATEGORIES.contains(&category) {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": "Catégorie inconnue"}))).into_response();
+    }
+    let page = params.get("page").and_then(|s| s.parse::<u64>().ok()).unwrap_or(1).max(1);
+    let per_page = params.get("per_page").and_then(|s| s.parse::<u64>().ok()).unwrap_or(50).clamp(1, 200);
+    let universe_id = match state.universes.resolve(&state.db, &params).await {
+        Ok(u) => u.id,
+        Err(code) => return (code, Json(json!({"error": "Univers introuvable"}))).into_response(),
+    };
+
+    // 1. Dernier instantané de la catégorie
+    let latest = ranking::Entity::find()
+        .filter(ranking::Column::UniverseId.eq(universe_id))
+        .filter(ranking::Column::Category.eq(category))
+        .order_by_desc(ranking::Column::SnapshotAt)
+        .one(&state.db)
+        .await
+        .unwrap_or(None);
+    let Some(latest) = latest else {
+        return Json(Vec::<RankItem>::new()).into_response();
+    };
+
+    let snapshot = ranking::Entity::find()
+        .filter(ranking::Column::UniverseId.eq(universe_id))
+        .filter(ranking::Column::Category.eq(category))
+        .filter(ranking::Column::SnapshotAt.eq(latest.snapshot_at));
+    let total = snapshot.clone().count(&state.db).await.unwrap_or(0);
+    let rows = snapshot
+        .order_by_asc(ranking::Column::Rank)
+        .offset((page - 1) * per_page)
+        .limit(per_page)
+        .all(&state.db)
+        .await
+        .unwrap_or_default();
+    let ids: Vec<Uuid> = rows.iter().map(|r| r.planet_id).collect();
+
+    // 2. Rangs de l'instantané précédent pour l'évolution
+    let previous = ranking::Entity::find()
+        .filter(ranking::Column::UniverseId.eq(universe_id))
+        .filter(ranking::Column::Category.eq(category))
+        .filter(ranking::Column::SnapshotAt.lt(latest.snapshot_at))
+        .order_by_desc(ranking::Column::SnapshotAt)
+        .one(&state.db)
+        .await
+        .unwrap_or(None);
+    let previous_ranks: HashMap<Uuid, i32> = match previous {
+        Some(prev) => ranking::Entity::find()
+            .filter(ranking::Column::UniverseId.eq(universe_id))
+            .filter(ranking::Column::Category.eq(category))
+            .filter(ranking::Column::SnapshotAt.eq(prev.snapshot_at))
+            .filter(ranking::Column::PlanetId.is_in(ids.clone()))
+            .all(&state.db)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|r| (r.planet_id, r.rank))
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    // 3. Protection calculée sur le score actuel
+    let my_score = planet::Entity::find_by_id(current_planet_id)
+        .one(&state.db)
+        .await
+        .ok()
+        .flatten()
+        .map(|p| game_logic::get_score(&p));
+    let tags = alliance::get_tags(&state.db, ids.clone()).await;
+    let live_scores: HashMap<Uuid, i32> = planet::Entity::find()
+        .filter(planet::Column::Id.is_in(ids))
+        .all(&state.db)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|p| (p.id, game_logic::get_score(p)))
+        .collect();
+
+    let items: Vec<RankItem> = rows.into_iter().map(|r| {
+        let is_me = r.planet_id == current_planet_id;
+        let protection = match (is_me, live_scores.get(&r.planet_id)) {
+            (false, Some(&score)) => match my_score {
+                Some(mine) => state.protection.check(mine, score),
+                None if state.protection.is_newbie(score) => Some("newbie"),
+                None => None,
+            },
+            _ => None,
+        };
+
+        RankItem {
+            rank: r.rank,
+            planet_name: r.planet_name,
+            score: r.score as i32,
+            is_me,
+            id: r.planet_id,
+            protection,
+            rank_change: previous_ranks.get(&r.planet_id).map(|prev| prev - r.rank),
+            alliance_tag: tags.get(&r.planet_id).cloned(),
+        }
+    }).collect();
+
+    (
+        [(HeaderName::from_static("x-total-count"), total.to_string())],
+        Json(items),
+    ).into_response()
+}
+
+// GET /ranking/history/:planet_id?category=total
+pub async fn get_ranking_history_handler(
+    Path(planet_id): Path<Uuid>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<Vec<ranking::Model>> {
+    let category = params.get("category").map(String::as_str).unwrap_or("total");
+
+    let history = ranking::Entity::find()
+        .filter(ranking::Column::PlanetId.eq(planet_id))
+        .filter(ranking::Column::Category.eq(category))
+        .order_by_desc(ranking::Column::SnapshotAt)
+        .limit(100)
+        .all(&state.db)
+        .await
+        .unwrap_or_default();
+
+    Json(history)
+}
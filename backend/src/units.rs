@@ -0,0 +1,74 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::planet_unit;
+use crate::fleet::SHIP_TYPES;
+
+pub const DEFENSE_TYPES: [&str; 8] = [
+    "missile_launcher", "light_laser", "heavy_laser", "ion_cannon", "gauss_cannon", "plasma_turret",
+    "small_shield_dome", "large_shield_dome",
+];
+
+// Vaisseaux et défenses stationnés sur une planète (type -> nombre)
+pub type Inventory = HashMap<String, i32>;
+
+pub fn is_known(unit_type: &str) -> bool {
+    SHIP_TYPES.contains(&unit_type) || DEFENSE_TYPES.contains(&unit_type)
+}
+
+pub fn count(inventory: &Inventory, unit_type: &str) -> i32 {
+    inventory.get(unit_type).copied().unwrap_or(0)
+}
+
+pub async fn load(db: &impl ConnectionTrait, planet_id: Uuid) -> Result<Inventory, DbErr> {
+    Ok(planet_unit::Entity::find()
+        .filter(planet_unit::Column::PlanetId.eq(planet_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.unit_type, u.count))
+        .collect())
+}
+
+// Fixe le nombre d'unités d'un type (la ligne disparaît à zéro)
+pub async fn set(db: &impl ConnectionTrait, planet_id: Uuid, unit_type: &str, count: i32) -> Result<(), DbErr> {
+    if count <= 0 {
+        planet_unit::Entity::delete_by_id((planet_id, unit_type.to_string())).exec(db).await?;
+        return Ok(());
+    }
+    let row = planet_unit::ActiveModel {
+        planet_id: Set(planet_id),
+        unit_type: Set(unit_type.to_string()),
+        count: Set(count),
+    };
+    planet_unit::Entity::insert(row)
+        .on_conflict(
+            OnConflict::columns([planet_unit::Column::PlanetId, planet_unit::Column::UnitType])
+                .update_column(planet_unit::Column::Count)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+// Ajoute (ou retire si négatif) des unités d'un type.
+// Lecture puis écriture : la planète doit être verrouillée par l'appelant.
+pub async fn add(db: &impl ConnectionTrait, planet_id: Uuid, unit_type: &str, qty: i32) -> Result<(), DbErr> {
+    let current = planet_unit::Entity::find_by_id((planet_id, unit_type.to_string()))
+        .one(db)
+        .await?
+        .map(|u| u.count)
+        .unwrap_or(0);
+    set(db, planet_id, unit_type, current + qty).await
+}
+
+// Ajoute (sign = 1) ou retire (sign = -1) toute une composition de flotte
+pub async fn add_all(db: &impl ConnectionTrait, planet_id: Uuid, units: &HashMap<String, i32>, sign: i32) -> Result<(), DbErr> {
+    for (unit_type, qty) in units {
+        add(db, planet_id, unit_type, sign * qty).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+use crate::entities::{planet, universe};
+use crate::{game_logic, AppState};
+
+// Réglages de tous les univers hébergés, lus au démarrage puis complétés à la demande
+// (univers créé après le démarrage). Le premier créé sert d'univers par défaut
+// (inscription, requêtes sans univers précisé).
+#[derive(Clone)]
+pub struct Universes(Arc<RwLock<Vec<game_logic::Universe>>>);
+
+impl Universes {
+    pub async fn load(db: &DatabaseConnection) -> Result<Self, DbErr> {
+        let rows = universe::Entity::find()
+            .order_by_asc(universe::Column::CreatedAt)
+            .all(db)
+            .await?;
+        if rows.is_empty() {
+            return Err(DbErr::RecordNotFound("Aucun univers configuré".into()));
+        }
+        Ok(Universes(Arc::new(RwLock::new(rows.iter().map(game_logic::Universe::from).collect()))))
+    }
+
+    fn cached(&self, id: Uuid) -> Option<game_logic::Universe> {
+        self.0.read().unwrap().iter().find(|u| u.id == id).copied()
+    }
+
+    // Réglages d'un univers ; absent du cache : relu en base (None s'il n'existe pas)
+    pub async fn get(&self, db: &impl ConnectionTrait, id: Uuid) -> Result<Option<game_logic::Universe>, DbErr> {
+        if let Some(u) = self.cached(id) {
+            return Ok(Some(u));
+        }
+        let Some(row) = universe::Entity::find_by_id(id).one(db).await? else {
+            return Ok(None);
+        };
+        let u = game_logic::Universe::from(&row);
+        let mut cache = self.0.write().unwrap();
+        if !cache.iter().any(|c| c.id == id) {
+            cache.push(u);
+        }
+        Ok(Some(u))
+    }
+
+    // Idem pour les handlers : 404 si l'univers n'existe pas
+    pub async fn require(&self, db: &impl ConnectionTrait, id: Uuid) -> Result<game_logic::Universe, StatusCode> {
+        match self.get(db, id).await {
+            Ok(Some(u)) => Ok(u),
+            Ok(None) => Err(StatusCode::NOT_FOUND),
+            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+
+    pub fn default_id(&self) -> Uuid {
+        self.0.read().unwrap()[0].id
+    }
+
+    // Univers visé par une requête : ?universe_id=..., sinon celui de ?current_planet_id=...,
+    // sinon l'univers par défaut. 404 si l'univers demandé n'existe pas.
+    pub async fn resolve(&self, db: &impl ConnectionTrait, params: &HashMap<String, String>) -> Result<game_logic::Universe, StatusCode> {
+        if let Some(raw) = params.get("universe_id") {
+            let id = Uuid::parse_str(raw).map_err(|_| StatusCode::NOT_FOUND)?;
+            return self.require(db, id).await;
+        }
+        let current = params.get("current_planet_id").and_then(|s| Uuid::parse_str(s).ok());
+        if let Some(id) = current {
+            if let Ok(Some(p)) = planet::Entity::find_by_id(id).one(db).await {
+                return self.require(db, p.universe_id).await;
+            }
+        }
+        self.require(db, self.default_id()).await
+    }
+}
+
+#[derive(Serialize)]
+pub struct UniverseView {
+    #[serde(flatten)]
+    universe: universe::Model,
+    players: u64,
+}
+
+// GET /universes — univers disponibles, leurs réglages et leur population
+pub async fn list_universes_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let rows = match universe::Entity::find()
+        .order_by_asc(universe::Column::CreatedAt)
+        .all(&state.db)
+        .await
+    {
+        Ok(rows) => rows,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Erreur DB"}))).into_response(),
+    };
+
+    let mut views = Vec::with_capacity(rows.len());
+    for u in rows {
+        let players = planet::Entity::find()
+            .filter(planet::Column::UniverseId.eq(u.id))
+            .filter(planet::Column::IsNpc.eq(false))
+            .count(&state.db)
+            .await
+            .unwrap_or(0);
+        views.push(UniverseView { universe: u, players });
+    }
+    Json(views).into_response()
+}
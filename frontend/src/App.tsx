@@ -4,7 +4,7 @@ import Shipyard from './components/Shipyard';
 import EmpireBar from './components/EmpireBar';
 import TechTree from './components/TechTree';
 import ExpeditionZone from './components/ExpeditionZone';
-import CombatModal from './components/CombatModal';
+import CombatModal, { UNIT_LABELS } from './components/CombatModal';
 import Login from './components/Login';
 import Leaderboard from './components/Leaderboard';
 import AttackModal from './components/AttackModal'; 
@@ -21,10 +21,7 @@ interface CombatReport {
   winner: string;
   log: string[];
   loot: number;
-  losses?: {
-    light_hunter: number;
-    cruiser: number;
-  };
+  losses?: Record<string, number>; // Unités perdues par type
 }
 
 export default function App() {
@@ -60,29 +57,39 @@ export default function App() {
       if (res.ok) {
         const data = await res.json();
         
-        // --- GESTION ALERTE DÉFENSE (Rapport entrant) ---
-        if (data.unread_report) {
+        // --- GESTION DES ALERTES (rapports non lus) ---
+        if (data.unread_notifications > 0) {
             try {
-                const reportData = JSON.parse(data.unread_report);
-                const isVictory = reportData.winner === 'defender'; 
-                
-                const formattedReport: CombatReport = {
-                    winner: isVictory ? 'player' : 'enemy',
-                    log: reportData.log,
-                    loot: reportData.loot,
-                    losses: reportData.losses
-                };
-
-                setCombatReport(formattedReport);
-                setShowCombatModal(true);
-                
-                // Nettoyage de l'alerte
-                await fetch(`http://localhost:8080/planets/${planetId}/clear-report`, {
-                    method: 'POST',
+                const notifRes = await fetch(`http://localhost:8080/planets/${planetId}/notifications?unread_only=true`, {
                     headers: { 'Authorization': `Bearer ${token}` }
                 });
+                const notifications = await notifRes.json();
+                const latest = notifications[0];
+
+                if (latest) {
+                    const reportData = latest.payload;
+                    const isVictory = latest.kind === 'defense_report'
+                        ? reportData.winner === 'defender'
+                        : reportData.winner === 'attacker' || reportData.winner === 'player';
+
+                    const formattedReport: CombatReport = {
+                        winner: isVictory ? 'player' : 'enemy',
+                        log: reportData.log,
+                        loot: reportData.loot,
+                        losses: reportData.losses
+                    };
+
+                    setCombatReport(formattedReport);
+                    setShowCombatModal(true);
+
+                    // Alerte affichée : on la marque comme lue
+                    await fetch(`http://localhost:8080/planets/${planetId}/notifications/${latest.id}/read`, {
+                        method: 'POST',
+                        headers: { 'Authorization': `Bearer ${token}` }
+                    });
+                }
             } catch (err) {
-                console.error("Erreur lecture rapport défense", err);
+                console.error("Erreur lecture des notifications", err);
             }
         }
 
@@ -97,10 +104,7 @@ export default function App() {
           metal_amount: data.metal_amount ?? 0,
           crystal_amount: data.crystal_amount ?? 0,
           deuterium_amount: data.deuterium_amount ?? 0,
-          energy_tech_level: data.energy_tech_level ?? 0,
-          metal_mine_level: data.metal_mine_level ?? 0,
-          crystal_mine_level: data.crystal_mine_level ?? 0,
-          deuterium_mine_level: data.deuterium_mine_level ?? 0,
+          levels: data.levels ?? {},
         };
 
         prevPlanetRef.current = sanitizedPlanet;
@@ -116,17 +120,24 @@ export default function App() {
 
   // --- ACTIONS ---
 
-  const launchExpedition = async () => {
+  const launchExpedition = async (ships: Record<string, number>, holdHours: number) => {
     if (!planetId || !token) return;
     try {
         const res = await fetch(`http://localhost:8080/planets/${planetId}/expedition`, {
             method: 'POST',
-            headers: { 'Authorization': `Bearer ${token}` }
+            headers: { 
+                'Authorization': `Bearer ${token}`,
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({
+                ships,
+                hold_hours: holdHours
+            })
         });
 
         if (res.ok) {
             const data = await res.json();
-            setPlanet(data.planet);
+            fetchPlanet(); // Vaisseaux partis : inventaire rechargé
             if (data.report) {
                 setCombatReport(data.report);
                 setShowCombatModal(true);
@@ -145,22 +156,24 @@ export default function App() {
     setTargetPlanet({ id: targetId, name: targetName });
   };
 
-  const handleConfirmAttack = async (hunters: number, cruisers: number) => {
+  const handleConfirmAttack = async (ships: Record<string, number>) => {
     if (!planetId || !token || !targetPlanet) return;
     setTargetPlanet(null);
     setReport("LANCEMENT DES VECTEURS D'ATTAQUE...");
 
+    // L'attaque est une mission de flotte : trajet, alerte chez la cible, soutiens alliés.
+    // Le rapport de combat arrive à l'impact, comme les autres rapports
     try {
-        const res = await fetch(`http://localhost:8080/attack?current_planet_id=${planetId}`, {
+        const res = await fetch(`http://localhost:8080/planets/${planetId}/send-fleet`, {
             method: 'POST',
             headers: { 
                 'Authorization': `Bearer ${token}`,
                 'Content-Type': 'application/json'
             },
             body: JSON.stringify({
+                mission: 'attack',
                 target_planet_id: targetPlanet.id,
-                hunters: hunters,
-                cruisers: cruisers
+                ships
             })
         });
 
@@ -168,19 +181,9 @@ export default function App() {
 
         if (res.ok) {
             fetchPlanet();
-            const pvpReport = data.report;
-            const formattedReport: CombatReport = {
-                winner: pvpReport.winner === 'attacker' ? 'player' : 'enemy',
-                log: pvpReport.log,
-                loot: (pvpReport.loot?.metal || 0) + (pvpReport.loot?.crystal || 0),
-                losses: {
-                    light_hunter: pvpReport.attacker_losses || 0,
-                    cruiser: 0
-                }
-            };
-            
-            setCombatReport(formattedReport);
-            setShowCombatModal(true);
+            const arrival = new Date(data.fleet.arrival_time).toLocaleTimeString();
+            setReport(`FLOTTE D'ATTAQUE EN ROUTE : IMPACT À ${arrival}`);
+            setTimeout(() => setReport(null), 5000);
         } else {
             setReport(`ERREUR ATTAQUE : ${data.error}`);
             setTimeout(() => setReport(null), 5000);
@@ -222,16 +225,18 @@ export default function App() {
 
             if (r.fleet) {
                 logs.push(">>> FLOTTE STATIONNÉE <<<");
-                logs.push(`Chasseurs: ${r.fleet.light_hunter}`);
-                logs.push(`Croiseurs: ${r.fleet.cruiser}`);
-                logs.push(`Recycleurs: ${r.fleet.recycler}`);
+                Object.entries(r.fleet as Record<string, number>)
+                    .filter(([, qty]) => qty > 0)
+                    .forEach(([ship, qty]) => logs.push(`${UNIT_LABELS[ship] ?? ship}: ${qty}`));
             } else if (r.detection_level === 'resources') {
                  logs.push(">>> FLOTTE: INCONNU (Tech insuffisante)");
             }
 
-            if (r.defense !== null && r.defense !== undefined) {
+            if (r.defense) {
                  logs.push(">>> DÉFENSES <<<");
-                 logs.push(`Batteries Laser: Niv. ${r.defense}`);
+                 Object.entries(r.defense as Record<string, number>)
+                     .filter(([, qty]) => qty > 0)
+                     .forEach(([defense, qty]) => logs.push(`${UNIT_LABELS[defense] ?? defense}: ${qty}`));
             }
 
             const formattedReport: CombatReport = {
@@ -257,7 +262,7 @@ export default function App() {
 
   // --- EFFETS ---
   useEffect(() => {
-    fetch('http://localhost:8080/config')
+    fetch(`http://localhost:8080/config${planetId ? `?current_planet_id=${planetId}` : ''}`)
       .then(res => res.json())
       .then(data => {
           console.log("Vitesse du jeu synchronisée:", data.speed_factor);
@@ -267,8 +272,29 @@ export default function App() {
 
     if (token && planetId) {
       fetchPlanet();
-      const interval = setInterval(fetchPlanet, 2000);
-      return () => clearInterval(interval);
+      // Filet de sécurité si le WebSocket est coupé
+      const interval = setInterval(fetchPlanet, 30000);
+
+      // Événements temps réel : ressources en direct, le reste déclenche un rafraîchissement
+      const socket = new WebSocket(`ws://localhost:8080/ws?token=${encodeURIComponent(token)}`);
+      socket.onmessage = (msg) => {
+        const event = JSON.parse(msg.data);
+        if (event.type === 'resources') {
+          setPlanet((prev: any) => prev && {
+            ...prev,
+            metal_amount: event.metal,
+            crystal_amount: event.crystal,
+            deuterium_amount: event.deuterium,
+          });
+        } else {
+          fetchPlanet();
+        }
+      };
+
+      return () => {
+        clearInterval(interval);
+        socket.close();
+      };
     }
   }, [token, planetId, fetchPlanet]);
 
@@ -318,7 +344,7 @@ export default function App() {
         {targetPlanet && planet && (
             <AttackModal 
                 targetName={targetPlanet.name}
-                myFleet={{ hunters: planet.light_hunter_count, cruisers: planet.cruiser_count }}
+                myFleet={planet.units || {}}
                 onConfirm={handleConfirmAttack}
                 onCancel={() => setTargetPlanet(null)}
             />
@@ -1,28 +1,53 @@
 import { useState } from "react";
-import { Crosshair, Shield, Rocket, AlertTriangle, X } from "lucide-react";
+import { Crosshair, Rocket, AlertTriangle, X, Package } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
+import { UNIT_LABELS } from './CombatModal';
+
+// Vaisseaux pouvant partir à l'attaque (mêmes types que fleet::SHIP_TYPES côté serveur),
+// avec leur attaque et leur soute de base (game_logic::get_ship_stats) pour les estimations
+const ATTACK_SHIPS: Record<string, { attack: number; cargo: number }> = {
+  light_hunter: { attack: 50, cargo: 50 },
+  heavy_hunter: { attack: 150, cargo: 100 },
+  cruiser: { attack: 400, cargo: 800 },
+  battleship: { attack: 1000, cargo: 1500 },
+  bomber: { attack: 1000, cargo: 500 },
+  destroyer: { attack: 2000, cargo: 2000 },
+  recycler: { attack: 1, cargo: 20000 },
+  spy_probe: { attack: 0, cargo: 0 },
+  small_cargo: { attack: 5, cargo: 5000 },
+  large_cargo: { attack: 5, cargo: 25000 },
+};
 
 interface AttackModalProps {
   targetName: string;
-  myFleet: {
-    hunters: number;
-    cruisers: number;
-  };
-  onConfirm: (hunters: number, cruisers: number) => void;
+  myFleet: Record<string, number>; // Vaisseaux à quai (planet.units)
+  onConfirm: (ships: Record<string, number>) => void;
   onCancel: () => void;
 }
 
 export default function AttackModal({ targetName, myFleet, onConfirm, onCancel }: AttackModalProps) {
-  const [hunters, setHunters] = useState(0);
-  const [cruisers, setCruisers] = useState(0);
+  const [selection, setSelection] = useState<Record<string, number>>({});
+
+  const available = Object.keys(ATTACK_SHIPS).filter(s => (myFleet[s] || 0) > 0);
+  // Quantités choisies, bornées par le hangar
+  const chosen = Object.fromEntries(
+    available
+      .map(s => [s, Math.min(selection[s] || 0, myFleet[s] || 0)] as [string, number])
+      .filter(([, qty]) => qty > 0)
+  );
+
+  // Estimations (juste pour l'UI) : puissance de feu et soute, qui plafonne le butin
+  const power = Object.entries(chosen).reduce((sum, [s, qty]) => sum + ATTACK_SHIPS[s].attack * qty, 0);
+  const cargo = Object.entries(chosen).reduce((sum, [s, qty]) => sum + ATTACK_SHIPS[s].cargo * qty, 0);
 
-  // Calcul de la puissance estimée (juste pour l'UI)
-  const power = (hunters * 10) + (cruisers * 50);
+  const setQty = (ship: string, value: number) => {
+    setSelection(prev => ({ ...prev, [ship]: Math.max(0, Math.min(myFleet[ship] || 0, value || 0)) }));
+  };
 
   const handleSubmit = () => {
-    if (hunters === 0 && cruisers === 0) return;
-    onConfirm(hunters, cruisers);
+    if (Object.keys(chosen).length === 0) return;
+    onConfirm(chosen);
   };
 
   return (
@@ -48,44 +73,38 @@ export default function AttackModal({ targetName, myFleet, onConfirm, onCancel }
         {/* Corps */}
         <div className="p-6 space-y-6">
             
-            {/* Sélection Chasseurs */}
-            <div className="space-y-2">
-                <div className="flex justify-between text-xs uppercase font-bold text-slate-400">
-                    <span>Chasseurs Légers</span>
-                    <span className="text-white">Dispo: {myFleet.hunters}</span>
-                </div>
-                <div className="flex gap-4 items-center">
-                    <Rocket size={20} className="text-slate-500" />
-                    <Input 
-                        type="range" 
-                        min="0" 
-                        max={myFleet.hunters} 
-                        value={hunters} 
-                        onChange={(e) => setHunters(parseInt(e.target.value))}
-                        className="flex-1 h-2 bg-slate-800 accent-red-500 cursor-pointer" 
-                    />
-                    <Input 
-                        type="number" 
-                        min="0" 
-                        max={myFleet.hunters} 
-                        value={hunters} 
-                        onChange={(e) => setHunters(Math.min(myFleet.hunters, parseInt(e.target.value) || 0))}
-                        className="w-20 bg-black border-red-900/50 text-white text-right font-mono"
-                    />
-                </div>
-            </div>
-
-            {/* Sélection Croiseurs */}
-            <div className="space-y-2 opacity-50 pointer-events-none grayscale"> {/* Grisé pour l'instant si pas implémenté */}
-                <div className="flex justify-between text-xs uppercase font-bold text-slate-400">
-                    <span>Croiseurs (Bientôt)</span>
-                    <span className="text-white">Dispo: {myFleet.cruisers}</span>
-                </div>
-                <div className="flex gap-4 items-center">
-                    <Shield size={20} className="text-slate-500" />
-                    <Input disabled type="range" value={0} className="flex-1 h-2 bg-slate-800" />
-                    <Input disabled type="number" value={0} className="w-20 bg-black border-slate-800 text-slate-500 text-right font-mono"/>
-                </div>
+            {/* Sélection de la flotte */}
+            {available.length === 0 && (
+                <p className="text-xs text-slate-500 uppercase font-bold text-center">Aucun vaisseau à quai</p>
+            )}
+            <div className="space-y-4 max-h-80 overflow-y-auto pr-1">
+                {available.map(ship => (
+                    <div key={ship} className="space-y-2">
+                        <div className="flex justify-between text-xs uppercase font-bold text-slate-400">
+                            <span>{UNIT_LABELS[ship] ?? ship}</span>
+                            <span className="text-white">Dispo: {myFleet[ship]}</span>
+                        </div>
+                        <div className="flex gap-4 items-center">
+                            <Rocket size={20} className="text-slate-500" />
+                            <Input 
+                                type="range" 
+                                min="0" 
+                                max={myFleet[ship]} 
+                                value={selection[ship] || 0} 
+                                onChange={(e) => setQty(ship, parseInt(e.target.value))}
+                                className="flex-1 h-2 bg-slate-800 accent-red-500 cursor-pointer" 
+                            />
+                            <Input 
+                                type="number" 
+                                min="0" 
+                                max={myFleet[ship]} 
+                                value={selection[ship] || 0} 
+                                onChange={(e) => setQty(ship, parseInt(e.target.value))}
+                                className="w-20 bg-black border-red-900/50 text-white text-right font-mono"
+                            />
+                        </div>
+                    </div>
+                ))}
             </div>
 
             {/* Résumé */}
@@ -93,9 +112,15 @@ export default function AttackModal({ targetName, myFleet, onConfirm, onCancel }
                 <div className="flex items-center gap-2 text-red-500 text-xs font-bold uppercase">
                     <AlertTriangle size={14} /> Zone de Guerre
                 </div>
-                <div className="text-right">
-                    <p className="text-[10px] text-slate-500 uppercase">Puissance de feu</p>
-                    <p className="text-xl font-black text-white">{power}</p>
+                <div className="flex gap-6 text-right">
+                    <div>
+                        <p className="text-[10px] text-slate-500 uppercase flex items-center gap-1 justify-end"><Package size={10} /> Soute</p>
+                        <p className="text-xl font-black text-white">{cargo.toLocaleString()}</p>
+                    </div>
+                    <div>
+                        <p className="text-[10px] text-slate-500 uppercase">Puissance de feu</p>
+                        <p className="text-xl font-black text-white">{power.toLocaleString()}</p>
+                    </div>
                 </div>
             </div>
 
@@ -106,7 +131,7 @@ export default function AttackModal({ targetName, myFleet, onConfirm, onCancel }
                 </Button>
                 <Button 
                     onClick={handleSubmit} 
-                    disabled={power === 0}
+                    disabled={Object.keys(chosen).length === 0}
                     className="bg-red-600 hover:bg-red-500 text-white font-black uppercase tracking-widest shadow-[0_0_20px_rgba(220,38,38,0.5)] disabled:opacity-50 disabled:cursor-not-allowed"
                 >
                     FEU À VOLONTÉ
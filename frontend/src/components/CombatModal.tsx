@@ -6,12 +6,31 @@ interface CombatReport {
   winner: string;
   log: string[];
   loot: number;
-  losses?: {
-    light_hunter: number;
-    cruiser: number;
-  };
+  losses?: Record<string, number>; // Unités perdues par type
 }
 
+// Libellés des unités dans le résumé des pertes
+export const UNIT_LABELS: Record<string, string> = {
+  light_hunter: 'Chasseurs',
+  heavy_hunter: 'Chasseurs Lourds',
+  cruiser: 'Croiseurs',
+  battleship: 'Vaisseaux de Bataille',
+  bomber: 'Bombardiers',
+  destroyer: 'Destructeurs',
+  recycler: 'Recycleurs',
+  spy_probe: 'Sondes',
+  small_cargo: 'Petits Transporteurs',
+  large_cargo: 'Grands Transporteurs',
+  missile_launcher: 'Lance-missiles',
+  light_laser: 'Lasers Légers',
+  heavy_laser: 'Lasers Lourds',
+  ion_cannon: 'Canons à Ions',
+  gauss_cannon: 'Canons de Gauss',
+  plasma_turret: 'Tourelles Plasma',
+  small_shield_dome: 'Petit Bouclier',
+  large_shield_dome: 'Grand Bouclier',
+};
+
 interface CombatModalProps {
   report: CombatReport | null;
   onClose: () => void;
@@ -56,7 +75,8 @@ export default function CombatModal({ report, onClose }: CombatModalProps) {
   const Icon = theme.icon;
 
   // Calcul s'il y a eu des pertes
-  const hasLosses = (report.losses?.light_hunter || 0) > 0 || (report.losses?.cruiser || 0) > 0;
+  const lostUnits = Object.entries(report.losses || {}).filter(([, qty]) => qty > 0);
+  const hasLosses = lostUnits.length > 0;
 
   return (
     <div className="fixed inset-0 z-[200] flex items-center justify-center bg-black/80 backdrop-blur-sm animate-in fade-in duration-300 p-4">
@@ -129,18 +149,12 @@ export default function CombatModal({ report, onClose }: CombatModalProps) {
                             <span className="text-xs uppercase font-bold text-red-400">Pertes Confirmées</span>
                         </div>
                         <div className="space-y-1 text-right">
-                            {(report.losses?.light_hunter || 0) > 0 && (
-                                <div className="flex justify-between items-center text-xs">
-                                    <span className="text-slate-400">Chasseurs</span>
-                                    <span className="text-red-500 font-bold">-{report.losses?.light_hunter}</span>
-                                </div>
-                            )}
-                            {(report.losses?.cruiser || 0) > 0 && (
-                                <div className="flex justify-between items-center text-xs">
-                                    <span className="text-slate-400">Croiseurs</span>
-                                    <span className="text-red-500 font-bold">-{report.losses?.cruiser}</span>
+                            {lostUnits.map(([unit, qty]) => (
+                                <div key={unit} className="flex justify-between items-center text-xs">
+                                    <span className="text-slate-400">{UNIT_LABELS[unit] ?? unit}</span>
+                                    <span className="text-red-500 font-bold">-{qty}</span>
                                 </div>
-                            )}
+                            ))}
                         </div>
                     </div>
                 )}
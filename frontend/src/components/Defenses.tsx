@@ -15,6 +15,50 @@ const DEFENSE_TYPES = [
     glow: 'shadow-[0_0_20px_rgba(96,165,250,0.5)]',
     bg: 'bg-blue-950/20'
   },
+  { 
+    id: 'light_laser', 
+    name: 'Laser Léger', 
+    tier: 'Défense Légère',
+    desc: 'Canon laser bon marché, remplace l\'ancienne batterie laser.',
+    m: 1500, c: 500, time: 10, atk: 100, def: 200,
+    color: 'text-red-400',
+    border: 'border-red-500',
+    glow: 'shadow-[0_0_20px_rgba(248,113,113,0.5)]',
+    bg: 'bg-red-950/20'
+  },
+  { 
+    id: 'heavy_laser', 
+    name: 'Laser Lourd', 
+    tier: 'Défense Moyenne',
+    desc: 'Version renforcée du laser, meilleure contre les chasseurs lourds.',
+    m: 6000, c: 2000, time: 30, atk: 250, def: 800,
+    color: 'text-orange-400',
+    border: 'border-orange-500',
+    glow: 'shadow-[0_0_20px_rgba(251,146,60,0.5)]',
+    bg: 'bg-orange-950/20'
+  },
+  { 
+    id: 'ion_cannon', 
+    name: 'Canon à Ions', 
+    tier: 'Défense Moyenne',
+    desc: 'Faible puissance de feu mais bouclier très épais.',
+    m: 2000, c: 6000, time: 30, atk: 150, def: 800,
+    color: 'text-sky-400',
+    border: 'border-sky-500',
+    glow: 'shadow-[0_0_20px_rgba(56,189,248,0.5)]',
+    bg: 'bg-sky-950/20'
+  },
+  { 
+    id: 'gauss_cannon', 
+    name: 'Canon de Gauss', 
+    tier: 'Artillerie',
+    desc: 'Projectiles accélérés magnétiquement, perce les vaisseaux de ligne.',
+    m: 20000, c: 15000, time: 80, atk: 1100, def: 3500,
+    color: 'text-indigo-400',
+    border: 'border-indigo-500',
+    glow: 'shadow-[0_0_20px_rgba(129,140,248,0.5)]',
+    bg: 'bg-indigo-950/20'
+  },
   { 
     id: 'plasma_turret', 
     name: 'Tourelle Plasma', 
@@ -25,6 +69,30 @@ const DEFENSE_TYPES = [
     border: 'border-pink-600',
     glow: 'shadow-[0_0_20px_rgba(236,72,153,0.5)]',
     bg: 'bg-pink-950/20'
+  },
+  { 
+    id: 'small_shield_dome', 
+    name: 'Petit Bouclier', 
+    tier: 'Dôme (1 max)',
+    desc: 'Dôme énergétique qui absorbe les tirs ennemis. Un seul par planète.',
+    m: 10000, c: 10000, time: 60, atk: 0, def: 2000,
+    color: 'text-teal-300',
+    border: 'border-teal-400',
+    glow: 'shadow-[0_0_20px_rgba(94,234,212,0.5)]',
+    bg: 'bg-teal-950/20',
+    unique: true
+  },
+  { 
+    id: 'large_shield_dome', 
+    name: 'Grand Bouclier', 
+    tier: 'Dôme (1 max)',
+    desc: 'Dôme planétaire massif. Un seul par planète.',
+    m: 50000, c: 50000, time: 180, atk: 0, def: 10000,
+    color: 'text-cyan-300',
+    border: 'border-cyan-400',
+    glow: 'shadow-[0_0_20px_rgba(103,232,249,0.5)]',
+    bg: 'bg-cyan-950/20',
+    unique: true
   }
 ];
 
@@ -55,7 +123,7 @@ export default function Defenses({ planet, onBuild }: { planet: any, onBuild: ()
 
   const startBuild = async () => {
     try {
-      const res = await fetch(`http://localhost:8080/planets/${planet.id}/build-fleet/${selected.id}/${qty}`, {
+      const res = await fetch(`http://localhost:8080/planets/${planet.id}/build-fleet/${selected.id}/${orderQty}`, {
         method: 'POST',
         headers: { 'Authorization': `Bearer ${localStorage.getItem('token')}` }
       });
@@ -63,8 +131,12 @@ export default function Defenses({ planet, onBuild }: { planet: any, onBuild: ()
     } catch (e) { console.error(e); }
   };
 
-  const totalM = selected.m * qty;
-  const totalC = selected.c * qty;
+  // Dômes : un seul exemplaire, quantité forcée à 1
+  const isUnique = 'unique' in selected && selected.unique === true;
+  const alreadyOwned = isUnique && (planet.units?.[selected.id] || 0) > 0;
+  const orderQty = isUnique ? 1 : qty;
+  const totalM = selected.m * orderQty;
+  const totalC = selected.c * orderQty;
   const canAfford = planet.metal_amount >= totalM && planet.crystal_amount >= totalC;
   const isBusy = planet.shipyard_construction_end !== null;
 
@@ -73,7 +145,7 @@ export default function Defenses({ planet, onBuild }: { planet: any, onBuild: ()
       
       {/* GAUCHE : SÉLECTEUR & COMMANDE */}
       <div className="lg:col-span-2 space-y-6">
-        <div className="grid grid-cols-2 gap-4">
+        <div className="grid grid-cols-2 md:grid-cols-4 gap-4">
           {DEFENSE_TYPES.map(d => {
             const isSelected = selected.id === d.id;
             return (
@@ -88,7 +160,7 @@ export default function Defenses({ planet, onBuild }: { planet: any, onBuild: ()
                         <span className={`text-[9px] font-black uppercase tracking-widest ${d.color}`}>{d.tier}</span>
                         <h3 className="text-sm font-black uppercase text-white">{d.name}</h3>
                     </div>
-                    {d.id === 'plasma_turret' ? <Zap size={20} className={d.color}/> : <Crosshair size={20} className={d.color}/>}
+                    {d.id === 'plasma_turret' ? <Zap size={20} className={d.color}/> : d.id.endsWith('_dome') ? <Shield size={20} className={d.color}/> : <Crosshair size={20} className={d.color}/>}
                  </div>
                  <span className="text-[10px] font-mono text-slate-400">{d.time}s / unité</span>
               </button>
@@ -123,7 +195,7 @@ export default function Defenses({ planet, onBuild }: { planet: any, onBuild: ()
                   <div className="space-y-2">
                       <label className="text-[10px] uppercase font-bold text-slate-500">Quantité</label>
                       <input 
-                        type="number" min="1" value={qty} 
+                        type="number" min="1" value={orderQty} disabled={isUnique}
                         onChange={e => setQty(Math.max(1, Number(e.target.value)))}
                         className="bg-transparent border-b border-white/20 text-3xl font-mono font-black text-white w-24 focus:outline-none"
                       />
@@ -136,10 +208,10 @@ export default function Defenses({ planet, onBuild }: { planet: any, onBuild: ()
 
               <Button 
                 onClick={startBuild}
-                disabled={isBusy || !canAfford}
-                className={`w-full h-14 uppercase font-black tracking-widest transition-all ${isBusy ? 'bg-slate-800 text-slate-500' : !canAfford ? 'bg-red-900/20 text-red-500 border border-red-500/50' : `bg-black hover:bg-slate-900 text-white border ${selected.border}`}`}
+                disabled={isBusy || !canAfford || alreadyOwned}
+                className={`w-full h-14 uppercase font-black tracking-widest transition-all ${isBusy || alreadyOwned ? 'bg-slate-800 text-slate-500' : !canAfford ? 'bg-red-900/20 text-red-500 border border-red-500/50' : `bg-black hover:bg-slate-900 text-white border ${selected.border}`}`}
               >
-                  {isBusy ? <span className="flex items-center gap-2"><Timer className="animate-spin" size={16}/> Occupé ({timeLeft}s)</span> : "Construire Défense"}
+                  {isBusy ? <span className="flex items-center gap-2"><Timer className="animate-spin" size={16}/> Occupé ({timeLeft}s)</span> : alreadyOwned ? "Dôme déjà installé" : "Construire Défense"}
               </Button>
            </div>
         </div>
@@ -152,14 +224,12 @@ export default function Defenses({ planet, onBuild }: { planet: any, onBuild: ()
            </h4>
            
            <div className="space-y-4">
-              <div className="flex justify-between items-center bg-white/5 p-3 rounded-lg border border-white/5">
-                 <span className="text-xs font-bold text-blue-400 uppercase">Lanceurs Missiles</span>
-                 <span className="text-xl text-white font-mono font-black">{planet.missile_launcher_count}</span>
-              </div>
-              <div className="flex justify-between items-center bg-white/5 p-3 rounded-lg border border-white/5">
-                 <span className="text-xs font-bold text-pink-500 uppercase">Tourelles Plasma</span>
-                 <span className="text-xl text-white font-mono font-black">{planet.plasma_turret_count}</span>
-              </div>
+              {DEFENSE_TYPES.map(d => (
+                <div key={d.id} className="flex justify-between items-center bg-white/5 p-3 rounded-lg border border-white/5">
+                   <span className={`text-xs font-bold uppercase ${d.color}`}>{d.name}</span>
+                   <span className="text-xl text-white font-mono font-black">{planet.units?.[d.id] || 0}</span>
+                </div>
+              ))}
               
               <div className="mt-8 p-4 bg-black/50 rounded-xl border border-white/5">
                   <div className="flex items-center gap-2 mb-2">
@@ -4,10 +4,16 @@ export default function EmpireBar({ planet }: { planet: any }) {
   if (!planet) return null;
 
   // Calcul du total de la flotte (Mise à jour avec les nouveaux vaisseaux)
-  const totalFleet = (planet.light_hunter_count || 0) 
-                   + (planet.cruiser_count || 0) 
-                   + (planet.recycler_count || 0) 
-                   + (planet.spy_probe_count || 0);
+  const totalFleet = (planet.units?.light_hunter || 0) 
+                   + (planet.units?.heavy_hunter || 0)
+                   + (planet.units?.cruiser || 0) 
+                   + (planet.units?.battleship || 0)
+                   + (planet.units?.bomber || 0)
+                   + (planet.units?.destroyer || 0)
+                   + (planet.units?.recycler || 0) 
+                   + (planet.units?.spy_probe || 0)
+                   + (planet.units?.small_cargo || 0)
+                   + (planet.units?.large_cargo || 0);
 
   // Formatter pour les grands nombres (ex: 1.2k, 1M)
   const formatNumber = (num: number) => {
@@ -75,7 +81,7 @@ export default function EmpireBar({ planet }: { planet: any }) {
                 <Zap size={10} /> Énergie
             </span>
             <span className="text-xs font-mono font-bold text-white">
-                {(planet.energy_tech_level * 500).toLocaleString()}
+                {((planet.levels?.energy_tech ?? 0) * 500).toLocaleString()}
             </span>
         </div>
 
@@ -2,10 +2,17 @@ import { useState, useEffect } from 'react';
 import { Compass, Timer, Send, AlertTriangle, Database, Rocket, Map, Radar, ScanLine } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card } from "@/components/ui/card";
+import { UNIT_LABELS } from './CombatModal';
 
-export default function ExpeditionZone({ planet, onAction }: { planet: any, onAction: () => void }) {
+// Vaisseaux pouvant partir en expédition (mêmes types que fleet::SHIP_TYPES côté serveur)
+const EXPEDITION_SHIPS = ['light_hunter', 'heavy_hunter', 'cruiser', 'battleship', 'bomber', 'destroyer', 'recycler', 'spy_probe', 'small_cargo', 'large_cargo'];
+const MAX_HOLD_HOURS = 8;
+
+export default function ExpeditionZone({ planet, onAction }: { planet: any, onAction: (ships: Record<string, number>, holdHours: number) => void }) {
   const [timeLeft, setTimeLeft] = useState<number | null>(null);
   const [isLaunching, setIsLaunching] = useState(false);
+  const [selection, setSelection] = useState<Record<string, number>>({});
+  const [holdHours, setHoldHours] = useState(1);
 
   // Gestion du compte à rebours visuel (purement UI)
   useEffect(() => {
@@ -31,13 +38,21 @@ export default function ExpeditionZone({ planet, onAction }: { planet: any, onAc
     }
   }, [planet?.expedition_end]);
 
+  const available = EXPEDITION_SHIPS.filter(s => (planet.units?.[s] || 0) > 0);
+  // Quantités choisies, bornées par le hangar
+  const chosen = Object.fromEntries(
+    available
+      .map(s => [s, Math.min(selection[s] || 0, planet.units?.[s] || 0)] as [string, number])
+      .filter(([, qty]) => qty > 0)
+  );
+
   const handleLaunch = () => {
     setIsLaunching(true);
-    onAction(); // On appelle la fonction du parent (App.tsx) qui fait le POST
+    onAction(chosen, holdHours); // On appelle la fonction du parent (App.tsx) qui fait le POST
   };
 
   const isInMission = (timeLeft !== null && timeLeft > 0) || isLaunching;
-  const hasShips = (planet.light_hunter_count || 0) > 0;
+  const hasShips = Object.keys(chosen).length > 0;
 
   // Thème de couleur pour cette section (Cyan/Exploration)
   const theme = {
@@ -83,13 +98,44 @@ export default function ExpeditionZone({ planet, onAction }: { planet: any, onAc
                    </h4>
                    <div className={`h-2 w-2 rounded-full ${hasShips ? 'bg-green-500 shadow-[0_0_10px_lime]' : 'bg-red-500 animate-pulse'}`}></div>
                 </div>
-                <div className="flex items-end gap-2">
-                   <span className={`text-3xl font-mono font-black ${hasShips ? 'text-white' : 'text-red-500'}`}>
-                     {planet.light_hunter_count || 0}
-                   </span>
-                   <span className="text-[10px] text-slate-500 uppercase font-bold mb-1">Chasseurs MK-I</span>
+                <div className="space-y-2">
+                   {available.map(s => (
+                     <div key={s} className="flex items-center justify-between gap-2">
+                        <span className="text-[10px] text-slate-400 uppercase font-bold">{UNIT_LABELS[s] ?? s}</span>
+                        <div className="flex items-center gap-2">
+                          <input
+                            type="number"
+                            min="0"
+                            max={planet.units?.[s] || 0}
+                            value={selection[s] || 0}
+                            onChange={e => setSelection({ ...selection, [s]: Math.max(0, Math.min(Number(e.target.value), planet.units?.[s] || 0)) })}
+                            className="w-20 bg-transparent border-b border-cyan-500/50 text-right font-mono font-black text-white outline-none focus:bg-white/5"
+                          />
+                          <button
+                            type="button"
+                            onClick={() => setSelection({ ...selection, [s]: planet.units?.[s] || 0 })}
+                            className="text-[9px] text-slate-500 hover:text-cyan-400 font-mono"
+                          >
+                            /{planet.units?.[s] || 0}
+                          </button>
+                        </div>
+                     </div>
+                   ))}
+                </div>
+                {available.length === 0 && <p className="text-[9px] text-red-400 mt-2 font-mono">&gt; ERROR: HANGAR VIDE</p>}
+
+                <div className="flex items-center justify-between gap-2 mt-4 pt-3 border-t border-white/5">
+                   <span className="text-[10px] text-slate-400 uppercase font-bold flex items-center gap-2"><Timer size={12}/> Stationnement</span>
+                   <select
+                     value={holdHours}
+                     onChange={e => setHoldHours(Number(e.target.value))}
+                     className="bg-black border border-cyan-500/50 rounded text-xs font-mono text-white px-2 py-1 outline-none"
+                   >
+                     {Array.from({ length: MAX_HOLD_HOURS }, (_, i) => i + 1).map(h => (
+                       <option key={h} value={h}>{h}h</option>
+                     ))}
+                   </select>
                 </div>
-                {!hasShips && <p className="text-[9px] text-red-400 mt-2 font-mono">&gt; ERROR: HANGAR VIDE</p>}
               </div>
 
               {/* Module Risques */}
@@ -1,9 +1,19 @@
-import { useState } from 'react';
-import { Power, User, ArrowRight, ShieldCheck, Lock } from "lucide-react";
+import { useEffect, useState } from 'react';
+import { Power, User, ArrowRight, ShieldCheck, Lock, Globe } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Card } from "@/components/ui/card";
 
+interface UniverseInfo {
+  id: string;
+  name: string;
+  economy_speed: number;
+  fleet_speed: number;
+  galaxies: number;
+  is_open: boolean;
+  players: number;
+}
+
 interface LoginProps {
   onLogin: (token: string, planetId: string) => void;
 }
@@ -14,6 +24,21 @@ export default function Login({ onLogin }: LoginProps) {
   const [isRegistering, setIsRegistering] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [loading, setLoading] = useState(false);
+  const [universes, setUniverses] = useState<UniverseInfo[]>([]);
+  const [universeId, setUniverseId] = useState('');
+
+  // Univers disponibles à l'inscription
+  useEffect(() => {
+    if (!isRegistering || universes.length > 0) return;
+    fetch('http://localhost:8080/universes')
+      .then(res => res.json())
+      .then((data: UniverseInfo[]) => {
+        setUniverses(data);
+        const firstOpen = data.find(u => u.is_open);
+        if (firstOpen) setUniverseId(firstOpen.id);
+      })
+      .catch(err => console.error("Impossible de charger les univers", err));
+  }, [isRegistering, universes.length]);
 
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -27,7 +52,9 @@ export default function Login({ onLogin }: LoginProps) {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         // On envoie maintenant le mot de passe
-        body: JSON.stringify({ username, password })
+        body: JSON.stringify(isRegistering && universeId
+          ? { username, password, universe_id: universeId }
+          : { username, password })
       });
 
       const data = await res.json();
@@ -95,6 +122,27 @@ export default function Login({ onLogin }: LoginProps) {
             </div>
           </div>
 
+          {/* Univers rejoint (inscription uniquement) */}
+          {isRegistering && universes.length > 0 && (
+            <div className="space-y-2">
+              <label className="text-[10px] uppercase font-bold text-slate-400 ml-1">Univers</label>
+              <div className="relative">
+                <Globe className="absolute left-3 top-1/2 -translate-y-1/2 text-slate-500" size={18} />
+                <select
+                  value={universeId}
+                  onChange={(e) => setUniverseId(e.target.value)}
+                  className="w-full pl-10 bg-white/5 border border-white/10 rounded-md text-white h-12 focus:border-indigo-500 transition-all"
+                >
+                  {universes.map(u => (
+                    <option key={u.id} value={u.id} disabled={!u.is_open} className="bg-black">
+                      {u.name} — x{u.economy_speed} · {u.galaxies} galaxies · {u.players} joueurs{u.is_open ? '' : ' (fermé)'}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            </div>
+          )}
+
           {error && (
             <div className="p-3 bg-red-900/20 border border-red-500/30 rounded text-red-400 text-xs flex items-center gap-2">
               <ShieldCheck size={14} /> {error}
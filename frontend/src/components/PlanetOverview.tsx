@@ -13,13 +13,16 @@ const calculateProduction = (level: number, baseFactor: number) => {
   };
 
   // Production par HEURE
-  const prodMetal = calculateProduction(planet.metal_mine_level, 30);
-  const prodCrystal = calculateProduction(planet.crystal_mine_level, 20);
-  const prodDeut = calculateProduction(planet.deuterium_mine_level, 10);
+  const prodMetal = calculateProduction(planet.levels?.metal ?? 0, 30);
+  const prodCrystal = calculateProduction(planet.levels?.crystal ?? 0, 20);
+  // Le deutérium rend mieux sur les planètes froides (même facteur que le backend)
+  const deutFactor = Math.max(0.1, 1.44 - 0.004 * (planet.temperature_max ?? 110));
+  const prodDeut = calculateProduction(planet.levels?.deuterium ?? 0, 10) * deutFactor;
 
   // Totaux Militaires
-  const totalFleet = (planet.light_hunter_count || 0) + (planet.cruiser_count || 0) + (planet.recycler_count || 0) + (planet.spy_probe_count || 0);
-  const totalDefense = (planet.missile_launcher_count || 0) + (planet.plasma_turret_count || 0);
+  const totalFleet = (planet.units?.light_hunter || 0) + (planet.units?.heavy_hunter || 0) + (planet.units?.cruiser || 0) + (planet.units?.battleship || 0) + (planet.units?.bomber || 0) + (planet.units?.destroyer || 0) + (planet.units?.recycler || 0) + (planet.units?.spy_probe || 0) + (planet.units?.small_cargo || 0) + (planet.units?.large_cargo || 0);
+  const totalDefense = ['missile_launcher', 'light_laser', 'heavy_laser', 'ion_cannon', 'gauss_cannon', 'plasma_turret', 'small_shield_dome', 'large_shield_dome']
+    .reduce((sum, d) => sum + (planet.units?.[d] || 0), 0);
 
   // Helper pour formater les chiffres
   const fmt = (n: number) => Math.floor(n).toLocaleString();
@@ -44,14 +47,18 @@ const calculateProduction = (level: number, baseFactor: number) => {
                 </div>
             </CardHeader>
             <CardContent>
-                <div className="grid grid-cols-2 gap-4 text-xs font-mono text-slate-300">
+                <div className="grid grid-cols-3 gap-4 text-xs font-mono text-slate-300">
                     <div className="bg-white/5 p-2 rounded border border-white/5">
                         <span className="text-slate-500 block uppercase text-[9px]">Diamètre</span>
-                        12,800 km
+                        {fmt(planet.diameter ?? 0)} km
                     </div>
                     <div className="bg-white/5 p-2 rounded border border-white/5">
                         <span className="text-slate-500 block uppercase text-[9px]">Température</span>
-                        approx. 14°C
+                        {planet.temperature_min}°C à {planet.temperature_max}°C
+                    </div>
+                    <div className={`bg-white/5 p-2 rounded border ${planet.fields_used >= planet.fields_max ? 'border-red-500/40 text-red-400' : 'border-white/5'}`}>
+                        <span className="text-slate-500 block uppercase text-[9px]">Cases</span>
+                        {planet.fields_used ?? 0} / {planet.fields_max ?? 0}
                     </div>
                 </div>
             </CardContent>
@@ -85,19 +85,21 @@ export default function ResourceDisplay({ planet, onUpgrade }: ResourceDisplayPr
   };
 
   const mines = [
-    { id: 'metal', name: 'Extracteur de Métal', lv: planet.metal_mine_level ?? 0, base: 30, icon: Pickaxe },
-    { id: 'crystal', name: 'Fonderie de Cristal', lv: planet.crystal_mine_level ?? 0, base: 20, icon: Gem },
-    { id: 'deuterium', name: 'Synthé de Deutérium', lv: planet.deuterium_mine_level ?? 0, base: 10, icon: Droplets },
+    { id: 'metal', name: 'Extracteur de Métal', lv: planet.levels?.metal ?? 0, base: 30, icon: Pickaxe },
+    { id: 'crystal', name: 'Fonderie de Cristal', lv: planet.levels?.crystal ?? 0, base: 20, icon: Gem },
+    { id: 'deuterium', name: 'Synthé de Deutérium', lv: planet.levels?.deuterium ?? 0, base: 10, icon: Droplets },
   ];
 
   const metalNow = planet.metal_amount ?? 0;
   const crystalNow = planet.crystal_amount ?? 0;
   const isBuilding = planet.construction_end !== null;
+  const isFull = (planet.fields_used ?? 0) >= (planet.fields_max ?? 0); // Plus aucune case libre
+  const deutFactor = Math.max(0.1, 1.44 - 0.004 * (planet.temperature_max ?? 110)); // Planètes froides : plus de deutérium
 
   return (
     <div className="grid grid-cols-1 md:grid-cols-3 gap-8 animate-in fade-in slide-in-from-bottom-4 duration-700">
       {mines.map((mine) => {
-        const prod = calculateProd(mine.lv, mine.base);
+        const prod = Math.floor(calculateProd(mine.lv, mine.base) * (mine.id === 'deuterium' ? deutFactor : 1));
         const cost = getNextCost(mine.id, mine.lv);
         const canAfford = metalNow >= cost.m && crystalNow >= cost.c;
         const isThisMineBuilding = planet.construction_type === mine.id;
@@ -167,9 +169,9 @@ export default function ResourceDisplay({ planet, onUpgrade }: ResourceDisplayPr
 
                 <Button 
                   onClick={() => handleUpgrade(mine.id)}
-                  disabled={isBuilding || !canAfford}
+                  disabled={isBuilding || !canAfford || isFull}
                   className={`w-full h-14 font-black uppercase text-xs tracking-[0.2em] transition-all rounded-xl relative overflow-hidden group/btn ${
-                    isBuilding 
+                    isBuilding || isFull
                       ? 'bg-slate-900 text-slate-500 border border-white/5' 
                       : !canAfford 
                         ? 'bg-red-950/30 text-red-500 border border-red-900/50 cursor-not-allowed'
@@ -185,6 +187,8 @@ export default function ResourceDisplay({ planet, onUpgrade }: ResourceDisplayPr
                      </span>
                   ) : isBuilding ? (
                     <span className="relative z-10">Chantier occupé</span>
+                  ) : isFull ? (
+                    <span className="relative z-10">Planète pleine</span>
                   ) : !canAfford ? (
                     <span className="relative z-10">Ressources manquantes</span>
                   ) : (
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { Rocket, Swords, Timer, Shield, Terminal, Zap, Box, Eye } from "lucide-react";
+import { Rocket, Swords, Timer, Shield, Terminal, Zap, Box, Eye, Package } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card } from "@/components/ui/card";
 
@@ -16,6 +16,17 @@ const SHIP_TYPES = [
     glow: 'shadow-[0_0_20px_rgba(251,146,60,0.5)]',
     bg: 'bg-orange-950/20'
   },
+  { 
+    id: 'heavy_hunter', 
+    name: 'Chasseur Lourd', 
+    tier: 'MK I+',
+    desc: 'Chasseur blindé, efficace contre les petits transporteurs.',
+    m: 6000, c: 4000, time: 30, atk: 150, def: 1000,
+    color: 'text-red-400',
+    border: 'border-red-500',
+    glow: 'shadow-[0_0_20px_rgba(248,113,113,0.5)]',
+    bg: 'bg-red-950/20'
+  },
   { 
     id: 'cruiser', 
     name: 'Croiseur', 
@@ -27,6 +38,39 @@ const SHIP_TYPES = [
     glow: 'shadow-[0_0_20px_rgba(34,211,238,0.5)]',
     bg: 'bg-cyan-950/20'
   },
+  { 
+    id: 'battleship', 
+    name: 'Vaisseau de Bataille', 
+    tier: 'MK III',
+    desc: 'Pilier de la flotte de ligne.',
+    m: 45000, c: 15000, time: 90, atk: 1000, def: 6000,
+    color: 'text-blue-400',
+    border: 'border-blue-500',
+    glow: 'shadow-[0_0_20px_rgba(96,165,250,0.5)]',
+    bg: 'bg-blue-950/20'
+  },
+  { 
+    id: 'bomber', 
+    name: 'Bombardier', 
+    tier: 'Siège',
+    desc: 'Lent mais dévastateur contre les défenses planétaires.',
+    m: 50000, c: 25000, time: 110, atk: 1000, def: 7500,
+    color: 'text-rose-400',
+    border: 'border-rose-500',
+    glow: 'shadow-[0_0_20px_rgba(251,113,133,0.5)]',
+    bg: 'bg-rose-950/20'
+  },
+  { 
+    id: 'destroyer', 
+    name: 'Destructeur', 
+    tier: 'MK IV',
+    desc: 'Bâtiment lourd, redoutable contre les vaisseaux de bataille.',
+    m: 60000, c: 50000, time: 150, atk: 2000, def: 11000,
+    color: 'text-fuchsia-400',
+    border: 'border-fuchsia-500',
+    glow: 'shadow-[0_0_20px_rgba(232,121,249,0.5)]',
+    bg: 'bg-fuchsia-950/20'
+  },
   { 
     id: 'recycler', 
     name: 'Recycleur', 
@@ -48,6 +92,28 @@ const SHIP_TYPES = [
     border: 'border-emerald-500',
     glow: 'shadow-[0_0_20px_rgba(52,211,153,0.5)]',
     bg: 'bg-emerald-950/20'
+  },
+  { 
+    id: 'small_cargo', 
+    name: 'Petit Transporteur', 
+    tier: 'Fret',
+    desc: 'Navette rapide de 5 000 unités de soute.',
+    m: 2000, c: 2000, time: 15, atk: 5, def: 400,
+    color: 'text-amber-300',
+    border: 'border-amber-400',
+    glow: 'shadow-[0_0_20px_rgba(252,211,77,0.5)]',
+    bg: 'bg-amber-950/20'
+  },
+  { 
+    id: 'large_cargo', 
+    name: 'Grand Transporteur', 
+    tier: 'Fret Lourd',
+    desc: 'Cargo de 25 000 unités pour la logistique entre colonies.',
+    m: 6000, c: 6000, time: 30, atk: 5, def: 1200,
+    color: 'text-yellow-500',
+    border: 'border-yellow-600',
+    glow: 'shadow-[0_0_20px_rgba(234,179,8,0.5)]',
+    bg: 'bg-yellow-950/20'
   }
 ];
 
@@ -97,9 +163,14 @@ export default function Shipyard({ planet, onBuild }: { planet: any, onBuild: ()
   // Helper pour l'icone
   const renderIcon = (id: string, size: number, className: string) => {
     switch(id) {
-        case 'cruiser': return <Shield size={size} className={className}/>;
+        case 'cruiser':
+        case 'battleship':
+        case 'destroyer': return <Shield size={size} className={className}/>;
+        case 'bomber': return <Swords size={size} className={className}/>;
         case 'recycler': return <Box size={size} className={className}/>;
         case 'spy_probe': return <Eye size={size} className={className}/>;
+        case 'small_cargo':
+        case 'large_cargo': return <Package size={size} className={className}/>;
         default: return <Rocket size={size} className={className}/>;
     }
   };
@@ -268,7 +339,7 @@ export default function Shipyard({ planet, onBuild }: { planet: any, onBuild: ()
                     <span className="text-[9px] uppercase font-bold text-orange-400">Chasseurs</span>
                     <span className="text-[8px] text-slate-500">Classe Légère</span>
                  </div>
-                 <span className="text-xl text-white font-mono font-black">{planet.light_hunter_count || 0}</span>
+                 <span className="text-xl text-white font-mono font-black">{planet.units?.light_hunter || 0}</span>
               </div>
               
               <div className="flex justify-between items-center bg-white/5 p-3 rounded-lg border border-white/5 group hover:bg-cyan-500/10 transition-colors">
@@ -276,7 +347,23 @@ export default function Shipyard({ planet, onBuild }: { planet: any, onBuild: ()
                     <span className="text-[9px] uppercase font-bold text-cyan-400">Croiseurs</span>
                     <span className="text-[8px] text-slate-500">Classe Lourde</span>
                  </div>
-                 <span className="text-xl text-white font-mono font-black">{planet.cruiser_count || 0}</span>
+                 <span className="text-xl text-white font-mono font-black">{planet.units?.cruiser || 0}</span>
+              </div>
+
+              <div className="flex justify-between items-center bg-white/5 p-3 rounded-lg border border-white/5 group hover:bg-blue-500/10 transition-colors">
+                 <div className="flex flex-col">
+                    <span className="text-[9px] uppercase font-bold text-blue-400">Ligne</span>
+                    <span className="text-[8px] text-slate-500">Ch. Lourds / Bataille</span>
+                 </div>
+                 <span className="text-xl text-white font-mono font-black">{planet.units?.heavy_hunter || 0} / {planet.units?.battleship || 0}</span>
+              </div>
+
+              <div className="flex justify-between items-center bg-white/5 p-3 rounded-lg border border-white/5 group hover:bg-rose-500/10 transition-colors">
+                 <div className="flex flex-col">
+                    <span className="text-[9px] uppercase font-bold text-rose-400">Lourds</span>
+                    <span className="text-[8px] text-slate-500">Bombardiers / Destructeurs</span>
+                 </div>
+                 <span className="text-xl text-white font-mono font-black">{planet.units?.bomber || 0} / {planet.units?.destroyer || 0}</span>
               </div>
 
               <div className="flex justify-between items-center bg-white/5 p-3 rounded-lg border border-white/5 group hover:bg-purple-500/10 transition-colors">
@@ -284,7 +371,7 @@ export default function Shipyard({ planet, onBuild }: { planet: any, onBuild: ()
                     <span className="text-[9px] uppercase font-bold text-purple-400">Recycleurs</span>
                     <span className="text-[8px] text-slate-500">Support</span>
                  </div>
-                 <span className="text-xl text-white font-mono font-black">{planet.recycler_count || 0}</span>
+                 <span className="text-xl text-white font-mono font-black">{planet.units?.recycler || 0}</span>
               </div>
 
               {/* Ligne ajoutée pour les Sondes */}
@@ -293,7 +380,15 @@ export default function Shipyard({ planet, onBuild }: { planet: any, onBuild: ()
                     <span className="text-[9px] uppercase font-bold text-emerald-400">Sondes</span>
                     <span className="text-[8px] text-slate-500">Espionnage</span>
                  </div>
-                 <span className="text-xl text-white font-mono font-black">{planet.spy_probe_count || 0}</span>
+                 <span className="text-xl text-white font-mono font-black">{planet.units?.spy_probe || 0}</span>
+              </div>
+
+              <div className="flex justify-between items-center bg-white/5 p-3 rounded-lg border border-white/5 group hover:bg-amber-500/10 transition-colors">
+                 <div className="flex flex-col">
+                    <span className="text-[9px] uppercase font-bold text-amber-300">Transporteurs</span>
+                    <span className="text-[8px] text-slate-500">Petits / Grands</span>
+                 </div>
+                 <span className="text-xl text-white font-mono font-black">{planet.units?.small_cargo || 0} / {planet.units?.large_cargo || 0}</span>
               </div>
            </div>
         </Card>
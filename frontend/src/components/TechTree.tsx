@@ -1,6 +1,6 @@
 import { useState, useEffect, useRef } from 'react';
 import { Card, CardContent } from "@/components/ui/card";
-import { Zap, Target, Atom, Microscope, Cpu, ArrowUpCircle, Sparkles, Eye, ScanLine, Lock, Loader2, AlertTriangle } from "lucide-react";
+import { Zap, Atom, Microscope, ArrowUpCircle, Sparkles, Eye, ScanLine, Lock, Loader2, AlertTriangle, Orbit, Package, Telescope, Compass } from "lucide-react";
 import { Button } from "@/components/ui/button";
 
 // Configuration visuelle (Inchangé)
@@ -9,8 +9,9 @@ const getTechConfig = (id: string, level: number) => {
   const configs: any = {
     research: { color: "text-purple-400", border: "border-purple-500/40", glow: "shadow-[0_0_20px_-5px_rgba(168,85,247,0.5)]", bg: "bg-purple-950/10", icon: Microscope, tierLabel: "LAB-OS", subIcon: Atom },
     energy_tech: { color: "text-yellow-400", border: "border-yellow-500/40", glow: "shadow-[0_0_20px_-5px_rgba(250,204,21,0.5)]", bg: "bg-yellow-950/10", icon: Zap, tierLabel: "CORE-REACT", subIcon: Sparkles },
-    laser: { color: "text-red-400", border: "border-red-500/40", glow: "shadow-[0_0_20px_-5px_rgba(248,113,113,0.5)]", bg: "bg-red-950/10", icon: Target, tierLabel: "WEAPON-SYS", subIcon: Cpu },
-    espionage: { color: "text-emerald-400", border: "border-emerald-500/40", glow: "shadow-[0_0_20px_-5px_rgba(52,211,153,0.5)]", bg: "bg-emerald-950/10", icon: Eye, tierLabel: "INTEL-NET", subIcon: ScanLine }
+    espionage: { color: "text-emerald-400", border: "border-emerald-500/40", glow: "shadow-[0_0_20px_-5px_rgba(52,211,153,0.5)]", bg: "bg-emerald-950/10", icon: Eye, tierLabel: "INTEL-NET", subIcon: ScanLine },
+    hyperspace: { color: "text-indigo-400", border: "border-indigo-500/40", glow: "shadow-[0_0_20px_-5px_rgba(129,140,248,0.5)]", bg: "bg-indigo-950/10", icon: Orbit, tierLabel: "HYPER-DRIVE", subIcon: Package },
+    astrophysics: { color: "text-cyan-400", border: "border-cyan-500/40", glow: "shadow-[0_0_20px_-5px_rgba(34,211,238,0.5)]", bg: "bg-cyan-950/10", icon: Telescope, tierLabel: "DEEP-SCAN", subIcon: Compass }
   };
   return { tier: `MK ${tier}`, ...configs[id] };
 };
@@ -21,8 +22,12 @@ const getCost = (type: string, level: number) => {
     switch(type) {
         case 'research': return { m: 200 * factor, c: 400 * factor, d: 200 * factor };
         case 'energy_tech': return { m: 0, c: 800 * factor, d: 400 * factor };
-        case 'laser': return { m: 1500 * factor, c: 500 * factor, d: 100 * factor };
         case 'espionage': return { m: 200 * factor, c: 1000 * factor, d: 200 * factor };
+        case 'hyperspace': return { m: 0, c: 4000 * factor, d: 2000 * factor };
+        case 'astrophysics': {
+            const astro = Math.pow(1.75, level - 1);
+            return { m: 4000 * astro, c: 8000 * astro, d: 4000 * astro };
+        }
         default: return { m: 0, c: 0, d: 0 };
     }
 };
@@ -36,7 +41,7 @@ export default function TechTree({ planet, onUpdate }: { planet: any, onUpdate:
 
   // LOGIQUE TIMER SÉCURISÉE
   useEffect(() => {
-    const isTechResearch = ['research', 'energy_tech', 'laser', 'espionage'].includes(planet.construction_type);
+    const isTechResearch = ['research', 'energy_tech', 'espionage', 'hyperspace', 'astrophysics'].includes(planet.construction_type);
 
     if (!planet?.construction_end || !isTechResearch) {
         setTimeLeft(null);
@@ -87,10 +92,11 @@ export default function TechTree({ planet, onUpdate }: { planet: any, onUpdate:
 
 
   const techs = [
-    { id: 'research', name: 'Labo de Recherche', lv: planet.research_lab_level, desc: "Traitement de données massives." },
-    { id: 'energy_tech', name: 'Technologie Énergie', lv: planet.energy_tech_level, desc: "Optimisation du plasma (+50 Max/Nv)." },
-    { id: 'laser', name: 'Batterie Laser', lv: planet.laser_battery_level, desc: "Défense orbitale automatisée." },
-    { id: 'espionage', name: 'Tech. Espionnage', lv: planet.espionage_tech_level, desc: "Algorithmes de cryptage avancés." }
+    { id: 'research', name: 'Labo de Recherche', lv: planet.levels?.research ?? 0, desc: "Traitement de données massives." },
+    { id: 'energy_tech', name: 'Technologie Énergie', lv: planet.levels?.energy_tech ?? 0, desc: "Optimisation du plasma (+50 Max/Nv)." },
+    { id: 'espionage', name: 'Tech. Espionnage', lv: planet.levels?.espionage ?? 0, desc: "Algorithmes de cryptage avancés." },
+    { id: 'hyperspace', name: 'Tech. Hyperespace', lv: planet.levels?.hyperspace ?? 0, desc: "Compression des soutes (+5% de fret/Nv)." },
+    { id: 'astrophysics', name: 'Astrophysique', lv: planet.levels?.astrophysics ?? 0, desc: "Expéditions simultanées (1 + racine du niveau)." }
   ];
 
   const handleResearch = async (type: string) => {